@@ -0,0 +1,805 @@
+use crate::app::App;
+use crate::batch::BatchAction;
+use crate::modes::Mode;
+use crate::workflow::{SettingsSection, StageField};
+
+/// Everything a key can do, shared by the key dispatcher and the command palette
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    SelectNext,
+    SelectPrevious,
+    OpenSelected,
+    ParentDirectory,
+    PreviewPageDown,
+    PreviewPageUp,
+    PreviewHalfPageDown,
+    PreviewHalfPageUp,
+    FindInPreview,
+    Quit,
+    NewNote,
+    NewFromTemplate,
+    ResumeEditing,
+    OpenInSplit,
+    OpenOutline,
+    OpenTasks,
+    OpenToday,
+    OpenCalendar,
+    OpenBoard,
+    OpenTimeline,
+    TogglePreviewTask,
+    Rename,
+    BulkRename,
+    TogglePin,
+    OpenSwitcher,
+    OpenVaultSwitcher,
+    CycleSort,
+    ToggleSortOrder,
+    ToggleDetails,
+    ChangeDirectory,
+    StartSearch,
+    OpenSettings,
+    Delete,
+    Move,
+    ToggleMark,
+    MarkAll,
+    Tag,
+    Export,
+    Copy,
+    OpenPalette,
+    OpenHelp,
+    ExitSearch,
+    SearchAccept,
+    EditSearchQuery,
+    StopEditing,
+    NextBuffer,
+    PreviousBuffer,
+    EditorPageDown,
+    EditorPageUp,
+    SaveBuffer,
+    CloseBuffer,
+    CycleSplit,
+    SwitchSplitFocus,
+    CycleLineNumbers,
+    CycleSoftWrap,
+    ToggleRuler,
+    ReflowParagraph,
+    ToggleTaskAtCursor,
+    PreviousDailyNote,
+    NextDailyNote,
+    CopyToClipboard,
+    OpenFind,
+    Undo,
+    CloseFind,
+    ToggleFindField,
+    FindNext,
+    FindPrevious,
+    ToggleFindRegex,
+    ToggleFindCase,
+    ReplaceCurrent,
+    ReplaceAll,
+    ExitPreviewFind,
+    PreviewFindNext,
+    PreviewFindPrevious,
+    CloseTimeline,
+    ToggleTimelineReport,
+    TimelineNext,
+    TimelinePrevious,
+    CloseHookLog,
+    CloseBoard,
+    OpenCard,
+    ShiftCardLeft,
+    ShiftCardRight,
+    ColumnLeft,
+    ColumnRight,
+    CardUp,
+    CardDown,
+    CloseCalendar,
+    OpenDay,
+    PreviousDay,
+    NextDay,
+    PreviousWeek,
+    NextWeek,
+    PreviousMonth,
+    NextMonth,
+    CalendarToday,
+    CloseTasks,
+    OpenTask,
+    CompleteTask,
+    CycleTaskGrouping,
+    TaskNext,
+    TaskPrevious,
+    CloseOutline,
+    JumpToHeading,
+    HideOutline,
+    OutlineNext,
+    OutlinePrevious,
+    CreateNote,
+    CancelNaming,
+    SubmitPrompt,
+    CancelPrompt,
+    ConfirmRename,
+    CancelRename,
+    SubmitTag,
+    CancelTagging,
+    ApplyStageEdit,
+    CancelStageEdit,
+    CloseSwitcher,
+    SwitcherOpen,
+    SwitcherNext,
+    SwitcherPrevious,
+    SwitcherTogglePin,
+    CloseVaultSwitcher,
+    SwitchVault,
+    VaultNext,
+    VaultPrevious,
+    CancelBulkRename,
+    ApplyBulkRename,
+    BulkRenameField,
+    BulkRenameCase,
+    BulkRenameDate,
+    BulkRenameNumbering,
+    Back,
+    OpenBrowserEntry,
+    SetRoot,
+    SetTemplateRoot,
+    SelectTemplate,
+    ConfirmBatch,
+    CancelBatch,
+    ConfirmDelete,
+    CancelOperation,
+    ExecuteMove,
+    MoveOptionNext,
+    MoveOptionPrevious,
+    SaveAllAndQuit,
+    DiscardAndQuit,
+    ExitSettings,
+    ToggleSettingsSection,
+    SettingsNext,
+    SettingsPrevious,
+    ApplyTheme,
+    AddStage,
+    DeleteStage,
+    StageUp,
+    StageDown,
+    CycleStageColor,
+    EditStageName,
+    EditStageFolder,
+    EditStageIcon,
+    EditStageTransitions,
+    EditStageWipLimit,
+    EditStagePreHook,
+    EditStagePostHook,
+    TrustVaultHooks,
+    CloseHelp,
+    HelpScrollDown,
+    HelpScrollUp,
+    HelpPageDown,
+    HelpPageUp,
+    ClosePalette,
+    RunPaletteEntry,
+    PaletteNext,
+    PalettePrevious,
+    CloseConfigErrors,
+}
+
+pub struct ActionSpec {
+    pub action: Action,
+    pub id: &'static str,
+    pub title: &'static str,
+}
+
+/// Default keys for an action in the modes where it applies
+pub struct Binding {
+    pub modes: &'static [Mode],
+    pub keys: &'static [&'static str],
+    pub action: Action,
+}
+
+pub const ACTIONS: &[ActionSpec] = &[
+    ActionSpec { action: Action::SelectNext, id: "select_next", title: "Select next entry" },
+    ActionSpec { action: Action::SelectPrevious, id: "select_previous", title: "Select previous entry" },
+    ActionSpec { action: Action::OpenSelected, id: "open_selected", title: "Open note or folder" },
+    ActionSpec { action: Action::ParentDirectory, id: "parent_directory", title: "Go up a directory" },
+    ActionSpec { action: Action::PreviewPageDown, id: "preview_page_down", title: "Scroll preview a page down" },
+    ActionSpec { action: Action::PreviewPageUp, id: "preview_page_up", title: "Scroll preview a page up" },
+    ActionSpec { action: Action::PreviewHalfPageDown, id: "preview_half_page_down", title: "Scroll preview half a page down" },
+    ActionSpec { action: Action::PreviewHalfPageUp, id: "preview_half_page_up", title: "Scroll preview half a page up" },
+    ActionSpec { action: Action::FindInPreview, id: "find_in_preview", title: "Find in preview" },
+    ActionSpec { action: Action::Quit, id: "quit", title: "Quit" },
+    ActionSpec { action: Action::NewNote, id: "new_note", title: "New note" },
+    ActionSpec { action: Action::NewFromTemplate, id: "new_from_template", title: "New note from template" },
+    ActionSpec { action: Action::ResumeEditing, id: "resume_editing", title: "Return to open buffers" },
+    ActionSpec { action: Action::OpenInSplit, id: "open_in_split", title: "Open note in a split" },
+    ActionSpec { action: Action::OpenOutline, id: "open_outline", title: "Document outline" },
+    ActionSpec { action: Action::OpenTasks, id: "open_tasks", title: "Tasks across the vault" },
+    ActionSpec { action: Action::OpenToday, id: "open_today", title: "Today's journal entry" },
+    ActionSpec { action: Action::OpenCalendar, id: "open_calendar", title: "Journal calendar" },
+    ActionSpec { action: Action::OpenBoard, id: "open_board", title: "Kanban board" },
+    ActionSpec { action: Action::OpenTimeline, id: "open_timeline", title: "Workflow history timeline" },
+    ActionSpec { action: Action::TogglePreviewTask, id: "toggle_preview_task", title: "Toggle first task in preview" },
+    ActionSpec { action: Action::Rename, id: "rename", title: "Rename note" },
+    ActionSpec { action: Action::BulkRename, id: "bulk_rename", title: "Bulk rename" },
+    ActionSpec { action: Action::TogglePin, id: "toggle_pin", title: "Pin or unpin" },
+    ActionSpec { action: Action::OpenSwitcher, id: "open_switcher", title: "Quick switcher" },
+    ActionSpec { action: Action::OpenVaultSwitcher, id: "open_vault_switcher", title: "Switch vault" },
+    ActionSpec { action: Action::CycleSort, id: "cycle_sort", title: "Cycle sort mode" },
+    ActionSpec { action: Action::ToggleSortOrder, id: "toggle_sort_order", title: "Reverse sort order" },
+    ActionSpec { action: Action::ToggleDetails, id: "toggle_details", title: "Toggle detail view" },
+    ActionSpec { action: Action::ChangeDirectory, id: "change_directory", title: "Change root directory" },
+    ActionSpec { action: Action::StartSearch, id: "search", title: "Search files" },
+    ActionSpec { action: Action::OpenSettings, id: "settings", title: "Settings" },
+    ActionSpec { action: Action::Delete, id: "delete", title: "Delete" },
+    ActionSpec { action: Action::Move, id: "move", title: "Move to workflow stage" },
+    ActionSpec { action: Action::ToggleMark, id: "toggle_mark", title: "Mark or unmark" },
+    ActionSpec { action: Action::MarkAll, id: "mark_all", title: "Mark or unmark all" },
+    ActionSpec { action: Action::Tag, id: "tag", title: "Add a tag" },
+    ActionSpec { action: Action::Export, id: "export", title: "Export into one note" },
+    ActionSpec { action: Action::Copy, id: "copy", title: "Copy to clipboard" },
+    ActionSpec { action: Action::OpenPalette, id: "command_palette", title: "Command palette" },
+    ActionSpec { action: Action::OpenHelp, id: "help", title: "Key bindings help" },
+    ActionSpec { action: Action::ExitSearch, id: "exit_search", title: "Exit search" },
+    ActionSpec { action: Action::SearchAccept, id: "search_accept", title: "Lock search or open result" },
+    ActionSpec { action: Action::EditSearchQuery, id: "edit_search_query", title: "Edit search query" },
+    ActionSpec { action: Action::StopEditing, id: "stop_editing", title: "Save and leave the editor" },
+    ActionSpec { action: Action::NextBuffer, id: "next_buffer", title: "Next buffer" },
+    ActionSpec { action: Action::PreviousBuffer, id: "previous_buffer", title: "Previous buffer" },
+    ActionSpec { action: Action::EditorPageDown, id: "editor_page_down", title: "Page down" },
+    ActionSpec { action: Action::EditorPageUp, id: "editor_page_up", title: "Page up" },
+    ActionSpec { action: Action::SaveBuffer, id: "save", title: "Save buffer" },
+    ActionSpec { action: Action::CloseBuffer, id: "close_buffer", title: "Save and close buffer" },
+    ActionSpec { action: Action::CycleSplit, id: "cycle_split", title: "Cycle split direction" },
+    ActionSpec { action: Action::SwitchSplitFocus, id: "switch_pane", title: "Switch split pane" },
+    ActionSpec { action: Action::CycleLineNumbers, id: "line_numbers", title: "Cycle line numbers" },
+    ActionSpec { action: Action::CycleSoftWrap, id: "soft_wrap", title: "Cycle soft wrap" },
+    ActionSpec { action: Action::ToggleRuler, id: "ruler", title: "Toggle ruler" },
+    ActionSpec { action: Action::ReflowParagraph, id: "reflow", title: "Reflow paragraph" },
+    ActionSpec { action: Action::ToggleTaskAtCursor, id: "toggle_task", title: "Toggle task checkbox" },
+    ActionSpec { action: Action::PreviousDailyNote, id: "previous_daily_note", title: "Previous journal entry" },
+    ActionSpec { action: Action::NextDailyNote, id: "next_daily_note", title: "Next journal entry" },
+    ActionSpec { action: Action::CopyToClipboard, id: "copy_note", title: "Copy note to clipboard" },
+    ActionSpec { action: Action::OpenFind, id: "find_replace", title: "Find and replace" },
+    ActionSpec { action: Action::Undo, id: "undo", title: "Undo" },
+    ActionSpec { action: Action::CloseFind, id: "close_find", title: "Close find bar" },
+    ActionSpec { action: Action::ToggleFindField, id: "find_field", title: "Switch find/replace field" },
+    ActionSpec { action: Action::FindNext, id: "find_next", title: "Next match" },
+    ActionSpec { action: Action::FindPrevious, id: "find_previous", title: "Previous match" },
+    ActionSpec { action: Action::ToggleFindRegex, id: "find_regex", title: "Toggle regex" },
+    ActionSpec { action: Action::ToggleFindCase, id: "find_case", title: "Toggle case sensitivity" },
+    ActionSpec { action: Action::ReplaceCurrent, id: "replace", title: "Replace match" },
+    ActionSpec { action: Action::ReplaceAll, id: "replace_all", title: "Replace all" },
+    ActionSpec { action: Action::ExitPreviewFind, id: "close_preview_find", title: "Close preview find" },
+    ActionSpec { action: Action::PreviewFindNext, id: "preview_find_next", title: "Next match" },
+    ActionSpec { action: Action::PreviewFindPrevious, id: "preview_find_previous", title: "Previous match" },
+    ActionSpec { action: Action::CloseTimeline, id: "close_timeline", title: "Close timeline" },
+    ActionSpec { action: Action::ToggleTimelineReport, id: "stage_report", title: "Toggle stage time report" },
+    ActionSpec { action: Action::TimelineNext, id: "timeline_next", title: "Next note" },
+    ActionSpec { action: Action::TimelinePrevious, id: "timeline_previous", title: "Previous note" },
+    ActionSpec { action: Action::CloseHookLog, id: "close_hook_log", title: "Close hook log" },
+    ActionSpec { action: Action::CloseBoard, id: "close_board", title: "Close board" },
+    ActionSpec { action: Action::OpenCard, id: "open_card", title: "Open card" },
+    ActionSpec { action: Action::ShiftCardLeft, id: "shift_card_left", title: "Move card to previous column" },
+    ActionSpec { action: Action::ShiftCardRight, id: "shift_card_right", title: "Move card to next column" },
+    ActionSpec { action: Action::ColumnLeft, id: "column_left", title: "Previous column" },
+    ActionSpec { action: Action::ColumnRight, id: "column_right", title: "Next column" },
+    ActionSpec { action: Action::CardUp, id: "card_up", title: "Previous card" },
+    ActionSpec { action: Action::CardDown, id: "card_down", title: "Next card" },
+    ActionSpec { action: Action::CloseCalendar, id: "close_calendar", title: "Close calendar" },
+    ActionSpec { action: Action::OpenDay, id: "open_day", title: "Open selected day" },
+    ActionSpec { action: Action::PreviousDay, id: "previous_day", title: "Previous day" },
+    ActionSpec { action: Action::NextDay, id: "next_day", title: "Next day" },
+    ActionSpec { action: Action::PreviousWeek, id: "previous_week", title: "Previous week" },
+    ActionSpec { action: Action::NextWeek, id: "next_week", title: "Next week" },
+    ActionSpec { action: Action::PreviousMonth, id: "previous_month", title: "Previous month" },
+    ActionSpec { action: Action::NextMonth, id: "next_month", title: "Next month" },
+    ActionSpec { action: Action::CalendarToday, id: "today", title: "Jump to today" },
+    ActionSpec { action: Action::CloseTasks, id: "close_tasks", title: "Close tasks" },
+    ActionSpec { action: Action::OpenTask, id: "open_task", title: "Open task's note" },
+    ActionSpec { action: Action::CompleteTask, id: "complete_task", title: "Complete task" },
+    ActionSpec { action: Action::CycleTaskGrouping, id: "task_grouping", title: "Cycle grouping" },
+    ActionSpec { action: Action::TaskNext, id: "task_next", title: "Next task" },
+    ActionSpec { action: Action::TaskPrevious, id: "task_previous", title: "Previous task" },
+    ActionSpec { action: Action::CloseOutline, id: "close_outline", title: "Close outline" },
+    ActionSpec { action: Action::JumpToHeading, id: "jump_to_heading", title: "Jump to heading" },
+    ActionSpec { action: Action::HideOutline, id: "hide_outline", title: "Hide outline" },
+    ActionSpec { action: Action::OutlineNext, id: "outline_next", title: "Next heading" },
+    ActionSpec { action: Action::OutlinePrevious, id: "outline_previous", title: "Previous heading" },
+    ActionSpec { action: Action::CreateNote, id: "create_note", title: "Create note" },
+    ActionSpec { action: Action::CancelNaming, id: "cancel_naming", title: "Cancel" },
+    ActionSpec { action: Action::SubmitPrompt, id: "submit_prompt", title: "Submit answer" },
+    ActionSpec { action: Action::CancelPrompt, id: "cancel_prompt", title: "Cancel" },
+    ActionSpec { action: Action::ConfirmRename, id: "confirm_rename", title: "Rename" },
+    ActionSpec { action: Action::CancelRename, id: "cancel_rename", title: "Cancel" },
+    ActionSpec { action: Action::SubmitTag, id: "submit_tag", title: "Add tag" },
+    ActionSpec { action: Action::CancelTagging, id: "cancel_tagging", title: "Cancel" },
+    ActionSpec { action: Action::ApplyStageEdit, id: "apply_stage_edit", title: "Apply" },
+    ActionSpec { action: Action::CancelStageEdit, id: "cancel_stage_edit", title: "Cancel" },
+    ActionSpec { action: Action::CloseSwitcher, id: "close_switcher", title: "Close switcher" },
+    ActionSpec { action: Action::SwitcherOpen, id: "switcher_open", title: "Open" },
+    ActionSpec { action: Action::SwitcherNext, id: "switcher_next", title: "Next entry" },
+    ActionSpec { action: Action::SwitcherPrevious, id: "switcher_previous", title: "Previous entry" },
+    ActionSpec { action: Action::SwitcherTogglePin, id: "switcher_pin", title: "Pin or unpin" },
+    ActionSpec { action: Action::CloseVaultSwitcher, id: "close_vault_switcher", title: "Close vault switcher" },
+    ActionSpec { action: Action::SwitchVault, id: "switch_vault", title: "Switch to vault" },
+    ActionSpec { action: Action::VaultNext, id: "vault_next", title: "Next vault" },
+    ActionSpec { action: Action::VaultPrevious, id: "vault_previous", title: "Previous vault" },
+    ActionSpec { action: Action::CancelBulkRename, id: "cancel_bulk_rename", title: "Cancel" },
+    ActionSpec { action: Action::ApplyBulkRename, id: "apply_bulk_rename", title: "Apply renames" },
+    ActionSpec { action: Action::BulkRenameField, id: "bulk_rename_field", title: "Switch find/replace field" },
+    ActionSpec { action: Action::BulkRenameCase, id: "bulk_rename_case", title: "Cycle case transform" },
+    ActionSpec { action: Action::BulkRenameDate, id: "bulk_rename_date", title: "Cycle date prefix" },
+    ActionSpec { action: Action::BulkRenameNumbering, id: "bulk_rename_numbering", title: "Toggle numbering" },
+    ActionSpec { action: Action::Back, id: "back", title: "Back" },
+    ActionSpec { action: Action::OpenBrowserEntry, id: "open_folder", title: "Open folder" },
+    ActionSpec { action: Action::SetRoot, id: "set_root", title: "Use as notes root" },
+    ActionSpec { action: Action::SetTemplateRoot, id: "set_template_root", title: "Use as template folder" },
+    ActionSpec { action: Action::SelectTemplate, id: "select_template", title: "Use template" },
+    ActionSpec { action: Action::ConfirmBatch, id: "confirm_batch", title: "Confirm" },
+    ActionSpec { action: Action::CancelBatch, id: "cancel_batch", title: "Cancel" },
+    ActionSpec { action: Action::ConfirmDelete, id: "confirm_delete", title: "Delete" },
+    ActionSpec { action: Action::CancelOperation, id: "cancel", title: "Cancel" },
+    ActionSpec { action: Action::ExecuteMove, id: "execute_move", title: "Move here" },
+    ActionSpec { action: Action::MoveOptionNext, id: "move_next", title: "Next stage" },
+    ActionSpec { action: Action::MoveOptionPrevious, id: "move_previous", title: "Previous stage" },
+    ActionSpec { action: Action::SaveAllAndQuit, id: "save_and_quit", title: "Save all and quit" },
+    ActionSpec { action: Action::DiscardAndQuit, id: "discard_and_quit", title: "Discard changes and quit" },
+    ActionSpec { action: Action::ExitSettings, id: "exit_settings", title: "Close settings" },
+    ActionSpec { action: Action::ToggleSettingsSection, id: "settings_section", title: "Switch themes/pipeline" },
+    ActionSpec { action: Action::SettingsNext, id: "settings_next", title: "Next entry" },
+    ActionSpec { action: Action::SettingsPrevious, id: "settings_previous", title: "Previous entry" },
+    ActionSpec { action: Action::ApplyTheme, id: "apply_theme", title: "Apply theme" },
+    ActionSpec { action: Action::AddStage, id: "add_stage", title: "Add stage" },
+    ActionSpec { action: Action::DeleteStage, id: "delete_stage", title: "Delete stage" },
+    ActionSpec { action: Action::StageUp, id: "stage_up", title: "Move stage up" },
+    ActionSpec { action: Action::StageDown, id: "stage_down", title: "Move stage down" },
+    ActionSpec { action: Action::CycleStageColor, id: "stage_color", title: "Cycle stage color" },
+    ActionSpec { action: Action::EditStageName, id: "stage_name", title: "Edit stage name" },
+    ActionSpec { action: Action::EditStageFolder, id: "stage_folder", title: "Edit stage folder" },
+    ActionSpec { action: Action::EditStageIcon, id: "stage_icon", title: "Edit stage icon" },
+    ActionSpec { action: Action::EditStageTransitions, id: "stage_transitions", title: "Edit allowed next stages" },
+    ActionSpec { action: Action::EditStageWipLimit, id: "stage_wip", title: "Edit WIP limit" },
+    ActionSpec { action: Action::EditStagePreHook, id: "stage_pre_hook", title: "Edit pre-move hook" },
+    ActionSpec { action: Action::EditStagePostHook, id: "stage_post_hook", title: "Edit post-move hook" },
+    ActionSpec { action: Action::TrustVaultHooks, id: "trust_vault_hooks", title: "Trust this vault's hooks" },
+    ActionSpec { action: Action::CloseHelp, id: "close_help", title: "Close help" },
+    ActionSpec { action: Action::HelpScrollDown, id: "help_down", title: "Scroll down" },
+    ActionSpec { action: Action::HelpScrollUp, id: "help_up", title: "Scroll up" },
+    ActionSpec { action: Action::HelpPageDown, id: "help_page_down", title: "Page down" },
+    ActionSpec { action: Action::HelpPageUp, id: "help_page_up", title: "Page up" },
+    ActionSpec { action: Action::ClosePalette, id: "close_palette", title: "Close palette" },
+    ActionSpec { action: Action::RunPaletteEntry, id: "run_palette_entry", title: "Run command" },
+    ActionSpec { action: Action::PaletteNext, id: "palette_next", title: "Next command" },
+    ActionSpec { action: Action::PalettePrevious, id: "palette_previous", title: "Previous command" },
+    ActionSpec { action: Action::CloseConfigErrors, id: "close_config_errors", title: "Dismiss" },
+];
+
+pub const DEFAULT_BINDINGS: &[Binding] = &[
+    Binding { modes: &[Mode::Normal, Mode::Search, Mode::ChangingDirectory, Mode::SelectingTemplateFolder, Mode::SelectingTemplate], keys: &["Down"], action: Action::SelectNext },
+    Binding { modes: &[Mode::Normal, Mode::Search, Mode::ChangingDirectory, Mode::SelectingTemplateFolder, Mode::SelectingTemplate], keys: &["Up"], action: Action::SelectPrevious },
+    Binding { modes: &[Mode::Normal], keys: &["Right", "Enter"], action: Action::OpenSelected },
+    Binding { modes: &[Mode::Search], keys: &["Right"], action: Action::OpenSelected },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["Left"], action: Action::ParentDirectory },
+    Binding { modes: &[Mode::Normal, Mode::PreviewFind], keys: &["PgDn"], action: Action::PreviewPageDown },
+    Binding { modes: &[Mode::Normal, Mode::PreviewFind], keys: &["PgUp"], action: Action::PreviewPageUp },
+    Binding { modes: &[Mode::Normal], keys: &["Ctrl+D"], action: Action::PreviewHalfPageDown },
+    Binding { modes: &[Mode::Normal], keys: &["Ctrl+U"], action: Action::PreviewHalfPageUp },
+    Binding { modes: &[Mode::Normal], keys: &["f"], action: Action::FindInPreview },
+    Binding { modes: &[Mode::Normal], keys: &["q"], action: Action::Quit },
+    Binding { modes: &[Mode::Normal], keys: &["n"], action: Action::NewNote },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+T"], action: Action::NewFromTemplate },
+    Binding { modes: &[Mode::Normal], keys: &["b"], action: Action::ResumeEditing },
+    Binding { modes: &[Mode::Normal], keys: &["v"], action: Action::OpenInSplit },
+    Binding { modes: &[Mode::Normal], keys: &["o"], action: Action::OpenOutline },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+L"], action: Action::OpenOutline },
+    Binding { modes: &[Mode::Normal], keys: &["t"], action: Action::OpenTasks },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+D"], action: Action::OpenToday },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+C"], action: Action::OpenCalendar },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+C"], action: Action::OpenCalendar },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+K"], action: Action::OpenBoard },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+W"], action: Action::OpenTimeline },
+    Binding { modes: &[Mode::Normal], keys: &["x"], action: Action::TogglePreviewTask },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["r"], action: Action::Rename },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["Shift+R"], action: Action::BulkRename },
+    Binding { modes: &[Mode::Normal], keys: &["p"], action: Action::TogglePin },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+P"], action: Action::OpenSwitcher },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+V"], action: Action::OpenVaultSwitcher },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+S"], action: Action::CycleSort },
+    Binding { modes: &[Mode::Normal], keys: &["Shift+O"], action: Action::ToggleSortOrder },
+    Binding { modes: &[Mode::Normal], keys: &["i"], action: Action::ToggleDetails },
+    Binding { modes: &[Mode::Normal], keys: &["c"], action: Action::ChangeDirectory },
+    Binding { modes: &[Mode::Normal], keys: &["/"], action: Action::StartSearch },
+    Binding { modes: &[Mode::Normal], keys: &["s"], action: Action::OpenSettings },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["d"], action: Action::Delete },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["m"], action: Action::Move },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["Space"], action: Action::ToggleMark },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["*"], action: Action::MarkAll },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["#"], action: Action::Tag },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["e"], action: Action::Export },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &["y"], action: Action::Copy },
+    Binding { modes: &[Mode::Normal, Mode::Editing, Mode::Search, Mode::Tasks, Mode::Calendar, Mode::Board, Mode::Timeline, Mode::Outline, Mode::Settings], keys: &["Ctrl+P"], action: Action::OpenPalette },
+    Binding { modes: &[Mode::Normal, Mode::Search], keys: &[":"], action: Action::OpenPalette },
+    Binding { modes: &[Mode::Normal, Mode::Editing, Mode::Search, Mode::Tasks, Mode::Calendar, Mode::Board, Mode::Timeline, Mode::Outline, Mode::Settings], keys: &["F1"], action: Action::OpenHelp },
+    Binding { modes: &[Mode::Normal], keys: &["?"], action: Action::OpenHelp },
+    Binding { modes: &[Mode::Search], keys: &["Esc"], action: Action::ExitSearch },
+    Binding { modes: &[Mode::Search], keys: &["Enter"], action: Action::SearchAccept },
+    Binding { modes: &[Mode::Search], keys: &["/"], action: Action::EditSearchQuery },
+    Binding { modes: &[Mode::Editing], keys: &["Esc"], action: Action::StopEditing },
+    Binding { modes: &[Mode::Editing], keys: &["Ctrl+PgDn"], action: Action::NextBuffer },
+    Binding { modes: &[Mode::Editing], keys: &["Ctrl+PgUp"], action: Action::PreviousBuffer },
+    Binding { modes: &[Mode::Editing], keys: &["PgDn"], action: Action::EditorPageDown },
+    Binding { modes: &[Mode::Editing], keys: &["PgUp"], action: Action::EditorPageUp },
+    Binding { modes: &[Mode::Editing], keys: &["Ctrl+S"], action: Action::SaveBuffer },
+    Binding { modes: &[Mode::Editing], keys: &["Ctrl+W"], action: Action::CloseBuffer },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+S"], action: Action::CycleSplit },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+O"], action: Action::SwitchSplitFocus },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+N"], action: Action::CycleLineNumbers },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+W"], action: Action::CycleSoftWrap },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+R"], action: Action::ToggleRuler },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+Q"], action: Action::ReflowParagraph },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+X"], action: Action::ToggleTaskAtCursor },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+["], action: Action::PreviousDailyNote },
+    Binding { modes: &[Mode::Editing], keys: &["Alt+]"], action: Action::NextDailyNote },
+    Binding { modes: &[Mode::Editing], keys: &["Ctrl+C"], action: Action::CopyToClipboard },
+    Binding { modes: &[Mode::Editing], keys: &["Ctrl+F"], action: Action::OpenFind },
+    Binding { modes: &[Mode::Editing, Mode::Finding], keys: &["Ctrl+U"], action: Action::Undo },
+    Binding { modes: &[Mode::Finding], keys: &["Esc"], action: Action::CloseFind },
+    Binding { modes: &[Mode::Finding], keys: &["Tab"], action: Action::ToggleFindField },
+    Binding { modes: &[Mode::Finding], keys: &["Enter", "Down"], action: Action::FindNext },
+    Binding { modes: &[Mode::Finding], keys: &["Up"], action: Action::FindPrevious },
+    Binding { modes: &[Mode::Finding], keys: &["Ctrl+X"], action: Action::ToggleFindRegex },
+    Binding { modes: &[Mode::Finding], keys: &["Ctrl+T"], action: Action::ToggleFindCase },
+    Binding { modes: &[Mode::Finding], keys: &["Ctrl+R"], action: Action::ReplaceCurrent },
+    Binding { modes: &[Mode::Finding], keys: &["Ctrl+A"], action: Action::ReplaceAll },
+    Binding { modes: &[Mode::PreviewFind], keys: &["Esc"], action: Action::ExitPreviewFind },
+    Binding { modes: &[Mode::PreviewFind], keys: &["Enter", "Down"], action: Action::PreviewFindNext },
+    Binding { modes: &[Mode::PreviewFind], keys: &["Up"], action: Action::PreviewFindPrevious },
+    Binding { modes: &[Mode::Timeline], keys: &["Esc"], action: Action::CloseTimeline },
+    Binding { modes: &[Mode::Timeline], keys: &["a"], action: Action::ToggleTimelineReport },
+    Binding { modes: &[Mode::Timeline], keys: &["Down"], action: Action::TimelineNext },
+    Binding { modes: &[Mode::Timeline], keys: &["Up"], action: Action::TimelinePrevious },
+    Binding { modes: &[Mode::HookLog], keys: &["Esc", "Enter"], action: Action::CloseHookLog },
+    Binding { modes: &[Mode::Board], keys: &["Esc"], action: Action::CloseBoard },
+    Binding { modes: &[Mode::Board], keys: &["Enter"], action: Action::OpenCard },
+    Binding { modes: &[Mode::Board], keys: &["Shift+Left", "Shift+H"], action: Action::ShiftCardLeft },
+    Binding { modes: &[Mode::Board], keys: &["Shift+Right", "Shift+L"], action: Action::ShiftCardRight },
+    Binding { modes: &[Mode::Board], keys: &["Left"], action: Action::ColumnLeft },
+    Binding { modes: &[Mode::Board], keys: &["Right"], action: Action::ColumnRight },
+    Binding { modes: &[Mode::Board], keys: &["Up"], action: Action::CardUp },
+    Binding { modes: &[Mode::Board], keys: &["Down"], action: Action::CardDown },
+    Binding { modes: &[Mode::Calendar], keys: &["Esc"], action: Action::CloseCalendar },
+    Binding { modes: &[Mode::Calendar], keys: &["Enter"], action: Action::OpenDay },
+    Binding { modes: &[Mode::Calendar], keys: &["Left"], action: Action::PreviousDay },
+    Binding { modes: &[Mode::Calendar], keys: &["Right"], action: Action::NextDay },
+    Binding { modes: &[Mode::Calendar], keys: &["Up"], action: Action::PreviousWeek },
+    Binding { modes: &[Mode::Calendar], keys: &["Down"], action: Action::NextWeek },
+    Binding { modes: &[Mode::Calendar], keys: &["PgUp"], action: Action::PreviousMonth },
+    Binding { modes: &[Mode::Calendar], keys: &["PgDn"], action: Action::NextMonth },
+    Binding { modes: &[Mode::Calendar], keys: &["t"], action: Action::CalendarToday },
+    Binding { modes: &[Mode::Tasks], keys: &["Esc"], action: Action::CloseTasks },
+    Binding { modes: &[Mode::Tasks], keys: &["Enter"], action: Action::OpenTask },
+    Binding { modes: &[Mode::Tasks], keys: &["Space", "x"], action: Action::CompleteTask },
+    Binding { modes: &[Mode::Tasks], keys: &["g"], action: Action::CycleTaskGrouping },
+    Binding { modes: &[Mode::Tasks], keys: &["Down"], action: Action::TaskNext },
+    Binding { modes: &[Mode::Tasks], keys: &["Up"], action: Action::TaskPrevious },
+    Binding { modes: &[Mode::Outline], keys: &["Esc"], action: Action::CloseOutline },
+    Binding { modes: &[Mode::Outline], keys: &["Enter"], action: Action::JumpToHeading },
+    Binding { modes: &[Mode::Outline], keys: &["x"], action: Action::HideOutline },
+    Binding { modes: &[Mode::Outline], keys: &["Down"], action: Action::OutlineNext },
+    Binding { modes: &[Mode::Outline], keys: &["Up"], action: Action::OutlinePrevious },
+    Binding { modes: &[Mode::Naming], keys: &["Enter"], action: Action::CreateNote },
+    Binding { modes: &[Mode::Naming], keys: &["Esc"], action: Action::CancelNaming },
+    Binding { modes: &[Mode::TemplatePrompt], keys: &["Enter"], action: Action::SubmitPrompt },
+    Binding { modes: &[Mode::TemplatePrompt], keys: &["Esc"], action: Action::CancelPrompt },
+    Binding { modes: &[Mode::Renaming], keys: &["Enter"], action: Action::ConfirmRename },
+    Binding { modes: &[Mode::Renaming], keys: &["Esc"], action: Action::CancelRename },
+    Binding { modes: &[Mode::Tagging], keys: &["Enter"], action: Action::SubmitTag },
+    Binding { modes: &[Mode::Tagging], keys: &["Esc"], action: Action::CancelTagging },
+    Binding { modes: &[Mode::EditingStage], keys: &["Enter"], action: Action::ApplyStageEdit },
+    Binding { modes: &[Mode::EditingStage], keys: &["Esc"], action: Action::CancelStageEdit },
+    Binding { modes: &[Mode::Switcher], keys: &["Esc"], action: Action::CloseSwitcher },
+    Binding { modes: &[Mode::Switcher], keys: &["Enter"], action: Action::SwitcherOpen },
+    Binding { modes: &[Mode::Switcher], keys: &["Down"], action: Action::SwitcherNext },
+    Binding { modes: &[Mode::Switcher], keys: &["Up"], action: Action::SwitcherPrevious },
+    Binding { modes: &[Mode::Switcher], keys: &["Tab"], action: Action::SwitcherTogglePin },
+    Binding { modes: &[Mode::VaultSwitcher], keys: &["Esc"], action: Action::CloseVaultSwitcher },
+    Binding { modes: &[Mode::VaultSwitcher], keys: &["Enter"], action: Action::SwitchVault },
+    Binding { modes: &[Mode::VaultSwitcher], keys: &["Down"], action: Action::VaultNext },
+    Binding { modes: &[Mode::VaultSwitcher], keys: &["Up"], action: Action::VaultPrevious },
+    Binding { modes: &[Mode::BulkRename], keys: &["Esc"], action: Action::CancelBulkRename },
+    Binding { modes: &[Mode::BulkRename], keys: &["Enter"], action: Action::ApplyBulkRename },
+    Binding { modes: &[Mode::BulkRename], keys: &["Tab"], action: Action::BulkRenameField },
+    Binding { modes: &[Mode::BulkRename], keys: &["Ctrl+E"], action: Action::BulkRenameCase },
+    Binding { modes: &[Mode::BulkRename], keys: &["Ctrl+D"], action: Action::BulkRenameDate },
+    Binding { modes: &[Mode::BulkRename], keys: &["Ctrl+N"], action: Action::BulkRenameNumbering },
+    Binding { modes: &[Mode::ChangingDirectory, Mode::SelectingTemplateFolder, Mode::SelectingTemplate, Mode::ConfirmingQuit], keys: &["Esc"], action: Action::Back },
+    Binding { modes: &[Mode::ChangingDirectory, Mode::SelectingTemplateFolder], keys: &["Enter"], action: Action::OpenBrowserEntry },
+    Binding { modes: &[Mode::ChangingDirectory], keys: &["s"], action: Action::SetRoot },
+    Binding { modes: &[Mode::SelectingTemplateFolder], keys: &["s"], action: Action::SetTemplateRoot },
+    Binding { modes: &[Mode::SelectingTemplate], keys: &["Enter"], action: Action::SelectTemplate },
+    Binding { modes: &[Mode::ConfirmingBatch], keys: &["y", "Shift+Y", "Enter"], action: Action::ConfirmBatch },
+    Binding { modes: &[Mode::ConfirmingBatch], keys: &["n", "Shift+N", "Esc"], action: Action::CancelBatch },
+    Binding { modes: &[Mode::ConfirmingDelete], keys: &["y", "Shift+Y", "Enter"], action: Action::ConfirmDelete },
+    Binding { modes: &[Mode::ConfirmingDelete], keys: &["n", "Shift+N", "Esc"], action: Action::CancelOperation },
+    Binding { modes: &[Mode::SelectingMoveDestination], keys: &["Esc"], action: Action::CancelOperation },
+    Binding { modes: &[Mode::SelectingMoveDestination], keys: &["Enter"], action: Action::ExecuteMove },
+    Binding { modes: &[Mode::SelectingMoveDestination], keys: &["Down"], action: Action::MoveOptionNext },
+    Binding { modes: &[Mode::SelectingMoveDestination], keys: &["Up"], action: Action::MoveOptionPrevious },
+    Binding { modes: &[Mode::ConfirmingQuit], keys: &["s", "Shift+S", "Enter"], action: Action::SaveAllAndQuit },
+    Binding { modes: &[Mode::ConfirmingQuit], keys: &["d", "Shift+D"], action: Action::DiscardAndQuit },
+    Binding { modes: &[Mode::Settings], keys: &["Esc"], action: Action::ExitSettings },
+    Binding { modes: &[Mode::Settings], keys: &["Tab"], action: Action::ToggleSettingsSection },
+    Binding { modes: &[Mode::Settings], keys: &["Down"], action: Action::SettingsNext },
+    Binding { modes: &[Mode::Settings], keys: &["Up"], action: Action::SettingsPrevious },
+    Binding { modes: &[Mode::Settings], keys: &["Enter"], action: Action::ApplyTheme },
+    Binding { modes: &[Mode::Settings], keys: &["a"], action: Action::AddStage },
+    Binding { modes: &[Mode::Settings], keys: &["x"], action: Action::DeleteStage },
+    Binding { modes: &[Mode::Settings], keys: &["Shift+K"], action: Action::StageUp },
+    Binding { modes: &[Mode::Settings], keys: &["Shift+J"], action: Action::StageDown },
+    Binding { modes: &[Mode::Settings], keys: &["c"], action: Action::CycleStageColor },
+    Binding { modes: &[Mode::Settings], keys: &["n", "Enter"], action: Action::EditStageName },
+    Binding { modes: &[Mode::Settings], keys: &["f"], action: Action::EditStageFolder },
+    Binding { modes: &[Mode::Settings], keys: &["i"], action: Action::EditStageIcon },
+    Binding { modes: &[Mode::Settings], keys: &["t"], action: Action::EditStageTransitions },
+    Binding { modes: &[Mode::Settings], keys: &["w"], action: Action::EditStageWipLimit },
+    Binding { modes: &[Mode::Settings], keys: &["h"], action: Action::EditStagePreHook },
+    Binding { modes: &[Mode::Settings], keys: &["Shift+H"], action: Action::EditStagePostHook },
+    Binding { modes: &[Mode::Settings], keys: &["Shift+T"], action: Action::TrustVaultHooks },
+    Binding { modes: &[Mode::Help], keys: &["Esc", "q"], action: Action::CloseHelp },
+    Binding { modes: &[Mode::Help], keys: &["Down"], action: Action::HelpScrollDown },
+    Binding { modes: &[Mode::Help], keys: &["Up"], action: Action::HelpScrollUp },
+    Binding { modes: &[Mode::Help], keys: &["PgDn"], action: Action::HelpPageDown },
+    Binding { modes: &[Mode::Help], keys: &["PgUp"], action: Action::HelpPageUp },
+    Binding { modes: &[Mode::CommandPalette], keys: &["Esc"], action: Action::ClosePalette },
+    Binding { modes: &[Mode::CommandPalette], keys: &["Enter"], action: Action::RunPaletteEntry },
+    Binding { modes: &[Mode::CommandPalette], keys: &["Down"], action: Action::PaletteNext },
+    Binding { modes: &[Mode::CommandPalette], keys: &["Up"], action: Action::PalettePrevious },
+    Binding { modes: &[Mode::ConfigErrors], keys: &["Esc", "Enter"], action: Action::CloseConfigErrors },
+    Binding { modes: &[Mode::ConfigErrors], keys: &["F1"], action: Action::OpenHelp },
+];
+
+impl Action {
+    pub fn spec(self) -> Option<&'static ActionSpec> {
+        ACTIONS.iter().find(|spec| spec.action == self)
+    }
+
+    pub fn title(self) -> &'static str {
+        self.spec().map(|spec| spec.title).unwrap_or_default()
+    }
+
+    pub fn id(self) -> &'static str {
+        self.spec().map(|spec| spec.id).unwrap_or_default()
+    }
+}
+
+impl<'a> App<'a> {
+    /// Whether bare character keys are text input in the current mode rather than commands
+    pub fn is_typing(&self) -> bool {
+        match self.mode {
+            Mode::Search => self.search_input_mode,
+            Mode::Editing
+            | Mode::Finding
+            | Mode::PreviewFind
+            | Mode::Naming
+            | Mode::TemplatePrompt
+            | Mode::Renaming
+            | Mode::Tagging
+            | Mode::EditingStage
+            | Mode::Switcher
+            | Mode::BulkRename
+            | Mode::SelectingTemplate
+            | Mode::CommandPalette => true,
+            _ => false,
+        }
+    }
+
+    /// Settings keys depend on which section is showing
+    pub fn action_available(&self, action: Action) -> bool {
+        match action {
+            Action::ApplyTheme => self.settings_section == SettingsSection::Themes,
+            Action::AddStage
+            | Action::DeleteStage
+            | Action::StageUp
+            | Action::StageDown
+            | Action::CycleStageColor
+            | Action::EditStageName
+            | Action::EditStageFolder
+            | Action::EditStageIcon
+            | Action::EditStageTransitions
+            | Action::EditStageWipLimit
+            | Action::EditStagePreHook
+            | Action::EditStagePostHook => self.settings_section == SettingsSection::Pipeline,
+            Action::TrustVaultHooks => self.settings_section == SettingsSection::Pipeline && self.hooks_need_trust(),
+            _ => true,
+        }
+    }
+
+    /// Actions usable in `mode` with their current keys, in registry order
+    pub fn mode_actions(&self, mode: Mode, typing: bool) -> Vec<(Action, Vec<String>)> {
+        ACTIONS
+            .iter()
+            .map(|spec| (spec.action, self.keymap.keys_for(mode, spec.action, typing)))
+            .filter(|(action, keys)| !keys.is_empty() && self.action_available(*action))
+            .collect()
+    }
+
+    pub fn run_action(&mut self, action: Action) {
+        match action {
+            Action::SelectNext => self.select_next(),
+            Action::SelectPrevious => self.select_previous(),
+            Action::OpenSelected => self.start_editing(),
+            Action::ParentDirectory => self.navigate_up_directory(),
+            Action::PreviewPageDown => self.preview_page_down(),
+            Action::PreviewPageUp => self.preview_page_up(),
+            Action::PreviewHalfPageDown => self.preview_half_page_down(),
+            Action::PreviewHalfPageUp => self.preview_half_page_up(),
+            Action::FindInPreview => self.enter_preview_find(),
+            Action::Quit => self.request_quit(),
+            Action::NewNote => self.mode = Mode::Naming,
+            Action::NewFromTemplate => self.start_template_workflow(),
+            Action::ResumeEditing => self.resume_editing(),
+            Action::OpenInSplit => self.open_in_split(),
+            Action::OpenOutline => self.open_outline(),
+            Action::OpenTasks => self.enter_tasks_mode(),
+            Action::OpenToday => self.open_today(),
+            Action::OpenCalendar => self.open_calendar(),
+            Action::OpenBoard => self.open_board(),
+            Action::OpenTimeline => self.open_timeline(),
+            Action::TogglePreviewTask => self.toggle_task_in_preview(),
+            Action::Rename => self.start_rename(),
+            Action::BulkRename => self.start_bulk_rename(),
+            Action::TogglePin => self.toggle_pin(),
+            Action::OpenSwitcher => self.open_switcher(),
+            Action::OpenVaultSwitcher => self.open_vault_switcher(),
+            Action::CycleSort => self.cycle_sort_key(),
+            Action::ToggleSortOrder => self.toggle_sort_order(),
+            Action::ToggleDetails => self.toggle_file_details(),
+            Action::ChangeDirectory => self.enter_directory_browser(false),
+            Action::StartSearch => self.enter_search_mode(),
+            Action::OpenSettings => self.enter_settings(),
+            Action::Delete => self.start_delete_confirmation(),
+            Action::Move => self.start_move_selection(),
+            Action::ToggleMark => self.toggle_mark(),
+            Action::MarkAll => self.toggle_mark_all(),
+            Action::Tag => self.start_tagging(),
+            Action::Export => self.request_batch(BatchAction::Export),
+            Action::Copy => self.request_batch(BatchAction::Copy),
+            Action::OpenPalette => self.open_palette(),
+            Action::OpenHelp => self.open_help(),
+            Action::ExitSearch => self.exit_search_mode(),
+            Action::SearchAccept => if self.search_input_mode { self.toggle_search_mode() } else { self.start_editing() },
+            Action::EditSearchQuery => self.search_input_mode = true,
+            Action::StopEditing => self.stop_editing(),
+            Action::NextBuffer => self.next_buffer(),
+            Action::PreviousBuffer => self.previous_buffer(),
+            Action::EditorPageDown => self.editor_page_down(),
+            Action::EditorPageUp => self.editor_page_up(),
+            Action::SaveBuffer => self.save_active_buffer(),
+            Action::CloseBuffer => self.close_active_buffer(),
+            Action::CycleSplit => self.cycle_split(),
+            Action::SwitchSplitFocus => self.switch_split_focus(),
+            Action::CycleLineNumbers => self.cycle_line_numbers(),
+            Action::CycleSoftWrap => self.cycle_soft_wrap(),
+            Action::ToggleRuler => self.toggle_ruler(),
+            Action::ReflowParagraph => self.reflow_current_paragraph(),
+            Action::ToggleTaskAtCursor => self.toggle_task_at_cursor(),
+            Action::PreviousDailyNote => self.step_daily_note(false),
+            Action::NextDailyNote => self.step_daily_note(true),
+            Action::CopyToClipboard => self.copy_file_to_clipboard(),
+            Action::OpenFind => self.open_find(),
+            Action::Undo => self.undo_edit(),
+            Action::CloseFind => self.close_find(),
+            Action::ToggleFindField => self.toggle_find_field(),
+            Action::FindNext => self.find_next(),
+            Action::FindPrevious => self.find_previous(),
+            Action::ToggleFindRegex => self.toggle_find_regex(),
+            Action::ToggleFindCase => self.toggle_find_case(),
+            Action::ReplaceCurrent => self.replace_current(),
+            Action::ReplaceAll => self.replace_all(),
+            Action::ExitPreviewFind => self.exit_preview_find(),
+            Action::PreviewFindNext => self.preview_find_next(),
+            Action::PreviewFindPrevious => self.preview_find_previous(),
+            Action::CloseTimeline => self.close_timeline(),
+            Action::ToggleTimelineReport => self.toggle_timeline_report(),
+            Action::TimelineNext => self.timeline_select(true),
+            Action::TimelinePrevious => self.timeline_select(false),
+            Action::CloseHookLog => self.close_hook_log(),
+            Action::CloseBoard => self.close_board(),
+            Action::OpenCard => self.board_open_card(),
+            Action::ShiftCardLeft => self.board_shift_card(false),
+            Action::ShiftCardRight => self.board_shift_card(true),
+            Action::ColumnLeft => self.board_move_column_focus(false),
+            Action::ColumnRight => self.board_move_column_focus(true),
+            Action::CardUp => self.board_move_card_focus(false),
+            Action::CardDown => self.board_move_card_focus(true),
+            Action::CloseCalendar => self.close_calendar(),
+            Action::OpenDay => self.calendar_open_selected(),
+            Action::PreviousDay => self.calendar_move_days(-1),
+            Action::NextDay => self.calendar_move_days(1),
+            Action::PreviousWeek => self.calendar_move_days(-7),
+            Action::NextWeek => self.calendar_move_days(7),
+            Action::PreviousMonth => self.calendar_move_months(false),
+            Action::NextMonth => self.calendar_move_months(true),
+            Action::CalendarToday => self.calendar_today(),
+            Action::CloseTasks => self.exit_tasks_mode(),
+            Action::OpenTask => self.open_selected_task(),
+            Action::CompleteTask => self.complete_selected_task(),
+            Action::CycleTaskGrouping => self.cycle_task_grouping(),
+            Action::TaskNext => self.task_next(),
+            Action::TaskPrevious => self.task_previous(),
+            Action::CloseOutline => self.close_outline(),
+            Action::JumpToHeading => self.jump_to_heading(),
+            Action::HideOutline => self.hide_outline(),
+            Action::OutlineNext => self.outline_next(),
+            Action::OutlinePrevious => self.outline_previous(),
+            Action::CreateNote => self.create_new_note(),
+            Action::CancelNaming => self.cancel_naming(),
+            Action::SubmitPrompt => self.submit_template_prompt(),
+            Action::CancelPrompt => self.cancel_template_prompt(),
+            Action::ConfirmRename => self.execute_rename(),
+            Action::CancelRename => self.cancel_rename(),
+            Action::SubmitTag => self.submit_tag(),
+            Action::CancelTagging => self.cancel_tagging(),
+            Action::ApplyStageEdit => self.apply_stage_edit(),
+            Action::CancelStageEdit => self.cancel_stage_edit(),
+            Action::CloseSwitcher => self.close_switcher(),
+            Action::SwitcherOpen => self.switcher_open(),
+            Action::SwitcherNext => self.switcher_select(true),
+            Action::SwitcherPrevious => self.switcher_select(false),
+            Action::SwitcherTogglePin => self.switcher_toggle_pin(),
+            Action::CloseVaultSwitcher => self.close_vault_switcher(),
+            Action::SwitchVault => self.vault_switcher_open(),
+            Action::VaultNext => self.vault_switcher_select(true),
+            Action::VaultPrevious => self.vault_switcher_select(false),
+            Action::CancelBulkRename => self.cancel_bulk_rename(),
+            Action::ApplyBulkRename => self.apply_bulk_rename(),
+            Action::BulkRenameField => self.bulk_rename_edit(|state| state.field = state.field.other()),
+            Action::BulkRenameCase => self.bulk_rename_edit(|state| state.case = state.case.next()),
+            Action::BulkRenameDate => self.bulk_rename_edit(|state| state.date_prefix = state.date_prefix.next()),
+            Action::BulkRenameNumbering => self.bulk_rename_edit(|state| state.numbering = !state.numbering),
+            Action::Back => self.mode = Mode::Normal,
+            Action::OpenBrowserEntry => self.select_browser_entry(),
+            Action::SetRoot => self.set_new_root(),
+            Action::SetTemplateRoot => self.set_template_root(),
+            Action::SelectTemplate => self.select_template(),
+            Action::ConfirmBatch => self.confirm_batch(),
+            Action::CancelBatch => self.cancel_batch(),
+            Action::ConfirmDelete => self.confirm_delete(),
+            Action::CancelOperation => self.cancel_operation(),
+            Action::ExecuteMove => self.execute_move(),
+            Action::MoveOptionNext => self.move_selection_next(),
+            Action::MoveOptionPrevious => self.move_selection_previous(),
+            Action::SaveAllAndQuit => self.save_all_and_quit(),
+            Action::DiscardAndQuit => self.discard_and_quit(),
+            Action::ExitSettings => self.exit_settings(),
+            Action::ToggleSettingsSection => self.toggle_settings_section(),
+            Action::SettingsNext => self.settings_next(),
+            Action::SettingsPrevious => self.settings_previous(),
+            Action::ApplyTheme => {
+                self.apply_color_scheme();
+                self.exit_settings();
+            }
+            Action::AddStage => self.add_stage(),
+            Action::DeleteStage => self.delete_stage(),
+            Action::StageUp => self.reorder_stage(false),
+            Action::StageDown => self.reorder_stage(true),
+            Action::CycleStageColor => self.cycle_stage_color(),
+            Action::EditStageName => self.start_stage_edit(StageField::Name),
+            Action::EditStageFolder => self.start_stage_edit(StageField::Folder),
+            Action::EditStageIcon => self.start_stage_edit(StageField::Icon),
+            Action::EditStageTransitions => self.start_stage_edit(StageField::Transitions),
+            Action::EditStageWipLimit => self.start_stage_edit(StageField::WipLimit),
+            Action::EditStagePreHook => self.start_stage_edit(StageField::PreHook),
+            Action::EditStagePostHook => self.start_stage_edit(StageField::PostHook),
+            Action::TrustVaultHooks => self.trust_vault_hooks(),
+            Action::CloseHelp => self.close_help(),
+            Action::HelpScrollDown => self.scroll_help(1),
+            Action::HelpScrollUp => self.scroll_help(-1),
+            Action::HelpPageDown => self.scroll_help(10),
+            Action::HelpPageUp => self.scroll_help(-10),
+            Action::ClosePalette => self.close_palette(),
+            Action::RunPaletteEntry => self.run_palette_entry(),
+            Action::PaletteNext => self.palette_select(true),
+            Action::PalettePrevious => self.palette_select(false),
+            Action::CloseConfigErrors => self.close_config_errors(),
+        }
+    }
+}
@@ -1,38 +1,107 @@
 use ratatui::widgets::{ListState};
-use std::{path::PathBuf, time::{SystemTime, UNIX_EPOCH}};
-use tui_textarea::TextArea;
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, path::PathBuf, time::{SystemTime, UNIX_EPOCH}};
 use fuzzy_matcher::skim::SkimMatcherV2;
 
 use crate::modes::Mode;
 use crate::ui::themes::ColorScheme;
-use crate::constants::DEFAULT_MOVE_DESTINATIONS;
 use crate::config;
+use crate::batch::BatchAction;
+use crate::board::BoardState;
+use crate::bookmarks::{Bookmarks, SwitcherState};
+use crate::buffers::Buffer;
+use crate::bulk_rename::BulkRenameState;
+use crate::editor_options::EditorOptions;
+use crate::file_ops::IgnoreList;
+use crate::file_sort::FileListOptions;
+use crate::find::FindState;
+use crate::history::TimelineState;
+use crate::journal::CalendarState;
+use crate::keymap::{KeyChord, Keymap};
+use crate::outline::Heading;
+use crate::palette::PaletteState;
+use crate::preview::PreviewCache;
+use crate::split::SplitState;
+use crate::workflow::{SettingsSection, StageField, WorkflowStage};
+use crate::templates::{PendingNote, TemplateEntry};
+use crate::tasks::{Task, TaskGrouping, TaskRow};
+use crate::vault::{Layer, NamedVault, VaultSwitcherState};
 
 pub struct App<'a> {
     pub mode: Mode,
     pub root: PathBuf,
+    /// The root picked in the config or the folder browser; `root` also follows the folders opened from the file list
+    pub notes_root: PathBuf,
     pub template_root: Option<PathBuf>,
+    pub daily_template: Option<PathBuf>,
     pub files: Vec<PathBuf>,
     pub file_list_state: ListState,
-    pub editor: Option<TextArea<'a>>,
+    pub buffers: Vec<Buffer<'a>>,
+    pub active_buffer: usize,
+    pub split: Option<SplitState>,
     pub filename_input: String,
     pub pending_template: Option<PathBuf>,
+    pub pending_note: Option<PendingNote>,
     pub browser_entries: Vec<PathBuf>,
     pub browser_state: ListState,
     pub current_browser_path: PathBuf,
-    pub template_files: Vec<PathBuf>,
+    pub template_files: Vec<TemplateEntry>,
+    pub filtered_templates: Vec<TemplateEntry>,
+    pub template_filter: String,
     pub template_list_state: ListState,
     pub search_input: String,
     pub search_input_mode: bool, // true = typing search, false = navigating results
     pub filtered_files: Vec<PathBuf>,
     pub fuzzy_matcher: SkimMatcherV2,
-    pub editing_file_path: Option<PathBuf>,
     pub operation_target_file: Option<PathBuf>,
-    pub move_destinations: Vec<String>,
-    pub workflow_folders: Vec<PathBuf>,
+    pub marked: BTreeSet<PathBuf>,
+    pub pending_batch: Option<BatchAction>,
+    pub bulk_rename: Option<BulkRenameState>,
+    pub stages: Vec<WorkflowStage>,
+    pub move_options: Vec<usize>,
     pub move_selection_state: ListState,
     pub color_scheme: ColorScheme,
+    /// The folder whose `.nostromo/config.toml` overrides the global settings, while the root is inside it
+    pub vault: Option<PathBuf>,
+    pub setting_layers: [Layer; 4],
+    pub vaults: Vec<NamedVault>,
+    pub vault_switcher: Option<VaultSwitcherState>,
+    /// Vaults whose hooks may run, with a hash of the hook commands that were trusted
+    pub trusted_vaults: BTreeMap<PathBuf, String>,
+    pub ignore: IgnoreList,
     pub settings_selection_state: ListState,
+    pub settings_section: SettingsSection,
+    pub stage_field: StageField,
+    pub editor_options: EditorOptions,
+    pub file_list_options: FileListOptions,
+    pub bookmarks: Bookmarks,
+    pub switcher: Option<SwitcherState>,
+    pub palette: Option<PaletteState>,
+    pub keymap: Keymap,
+    pub pending_keys: Vec<KeyChord>,
+    pub help_return_mode: Mode,
+    pub help_scroll: u16,
+    /// Problems found reading config.toml, shown at startup and on the help screen
+    pub config_errors: Vec<String>,
+    pub find: FindState,
+    pub outline: Vec<Heading>,
+    pub outline_state: ListState,
+    pub show_outline: bool,
+    pub outline_return_mode: Mode,
+    pub preview_scroll: HashMap<PathBuf, usize>,
+    pub preview_cache: Option<PreviewCache>,
+    pub preview_height: u16,
+    pub preview_find: String,
+    pub tasks: Vec<Task>,
+    pub task_rows: Vec<TaskRow>,
+    pub task_list_state: ListState,
+    pub task_grouping: TaskGrouping,
+    pub calendar: Option<CalendarState>,
+    pub board: Option<BoardState>,
+    pub hook_log: Vec<String>,
+    pub history_frontmatter: bool,
+    pub timeline: Option<TimelineState>,
+    pub hook_return_mode: Mode,
+    pub should_quit: bool,
     // Animation timing fields
     pub app_start_time: u64,
     pub last_update_time: u64,
@@ -40,7 +109,7 @@ pub struct App<'a> {
 
 impl<'a> App<'a> {
     pub fn new() -> Self {
-        let (root, template_root, color_scheme, workflow_folders) = config::load_config();
+        let config = config::load_config();
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
@@ -48,32 +117,84 @@ impl<'a> App<'a> {
         
         let mut app = Self {
             mode: Mode::Normal,
-            root,
-            template_root,
+            root: config.root.clone(),
+            notes_root: config.root,
+            template_root: None,
+            daily_template: config.daily_template,
             files: Vec::new(),
             file_list_state: ListState::default(),
-            editor: None,
+            buffers: Vec::new(),
+            active_buffer: 0,
+            split: None,
             filename_input: String::new(),
             pending_template: None,
+            pending_note: None,
             browser_entries: Vec::new(),
             browser_state: ListState::default(),
             current_browser_path: PathBuf::from("."),
             template_files: Vec::new(),
+            filtered_templates: Vec::new(),
+            template_filter: String::new(),
             template_list_state: ListState::default(),
             search_input: String::new(),
             search_input_mode: true,
             filtered_files: Vec::new(),
             fuzzy_matcher: SkimMatcherV2::default(),
-            editing_file_path: None,
             operation_target_file: None,
-            move_destinations: DEFAULT_MOVE_DESTINATIONS.iter().map(|s| s.to_string()).collect(),
-            workflow_folders,
+            marked: BTreeSet::new(),
+            pending_batch: None,
+            bulk_rename: None,
+            stages: Vec::new(),
+            move_options: Vec::new(),
             move_selection_state: ListState::default(),
-            color_scheme,
+            color_scheme: ColorScheme::Green,
+            vault: None,
+            setting_layers: [Layer::Default; 4],
+            vaults: config.vaults,
+            vault_switcher: None,
+            trusted_vaults: config.trusted_vaults,
+            ignore: IgnoreList::default(),
             settings_selection_state: ListState::default(),
+            settings_section: SettingsSection::Themes,
+            stage_field: StageField::Name,
+            editor_options: config.editor_options,
+            file_list_options: config.file_list_options,
+            bookmarks: config.bookmarks,
+            switcher: None,
+            palette: None,
+            keymap: config.keymap,
+            pending_keys: Vec::new(),
+            help_return_mode: Mode::Normal,
+            help_scroll: 0,
+            config_errors: config.errors,
+            find: FindState::new(),
+            outline: Vec::new(),
+            outline_state: ListState::default(),
+            show_outline: false,
+            outline_return_mode: Mode::Normal,
+            preview_scroll: HashMap::new(),
+            preview_cache: None,
+            preview_height: 0,
+            preview_find: String::new(),
+            tasks: Vec::new(),
+            task_rows: Vec::new(),
+            task_list_state: ListState::default(),
+            task_grouping: TaskGrouping::Note,
+            calendar: None,
+            board: None,
+            hook_log: Vec::new(),
+            history_frontmatter: config.history_frontmatter,
+            timeline: None,
+            hook_return_mode: Mode::Normal,
+            should_quit: false,
             app_start_time: current_time,
             last_update_time: current_time,
         };
+        let vault_errors = app.apply_layers(config.settings);
+        app.config_errors.extend(vault_errors);
+        if !app.config_errors.is_empty() {
+            app.mode = Mode::ConfigErrors;
+        }
         
         app.load_files();
         if !app.files.is_empty() {
@@ -82,10 +203,6 @@ impl<'a> App<'a> {
         app
     }
 
-    pub fn save_config(&self) {
-        config::save_config(&self.root, &self.template_root, &self.color_scheme, &self.workflow_folders);
-    }
-
     pub fn get_current_files(&self) -> &Vec<PathBuf> {
         if self.mode == Mode::Search {
             &self.filtered_files
@@ -93,4 +210,10 @@ impl<'a> App<'a> {
             &self.files
         }
     }
+
+    pub fn selected_file(&self) -> Option<&PathBuf> {
+        self.file_list_state
+            .selected()
+            .and_then(|index| self.get_current_files().get(index))
+    }
 }
\ No newline at end of file
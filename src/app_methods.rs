@@ -4,11 +4,16 @@ use ratatui::widgets::{Block, Borders, BorderType};
 use tui_textarea::TextArea;
 use fuzzy_matcher::FuzzyMatcher;
 
+use crate::actions::Action;
 use crate::app::App;
 use crate::clipboard;
 use crate::modes::Mode;
 use crate::ui::themes::ColorScheme;
 use crate::file_ops::{load_files, load_browser_entries, load_template_files};
+use crate::file_sort::sort_entries;
+use crate::batch::BatchAction;
+use crate::templates::TemplateEntry;
+use crate::workflow::SettingsSection;
 
 impl<'a> App<'a> {
     pub fn update_timing(&mut self) {
@@ -19,7 +24,9 @@ impl<'a> App<'a> {
     }
 
     pub fn load_files(&mut self) {
-        self.files = load_files(&self.root);
+        let mut files = load_files(&self.root, &self.ignore);
+        sort_entries(&mut files, self.current_sort());
+        self.files = files;
     }
 
     pub fn load_browser_entries(&mut self) {
@@ -46,10 +53,13 @@ impl<'a> App<'a> {
 
     pub fn set_new_root(&mut self) {
         self.root = self.current_browser_path.clone();
+        self.notes_root = self.root.clone();
+        // Before the vault check, which may open its error popup
+        self.mode = Mode::Normal;
+        self.refresh_vault();
         self.save_config();
         self.load_files();
         self.file_list_state.select(Some(0));
-        self.mode = Mode::Normal;
     }
 
     pub fn set_template_root(&mut self) {
@@ -60,8 +70,12 @@ impl<'a> App<'a> {
 
     pub fn start_template_workflow(&mut self) {
         if let Some(template_root) = &self.template_root {
-            self.template_files = load_template_files(template_root);
-            self.template_list_state.select(Some(0));
+            self.template_files = load_template_files(template_root)
+                .into_iter()
+                .map(|path| TemplateEntry::load(template_root, path))
+                .collect();
+            self.template_filter.clear();
+            self.update_filtered_templates();
             self.mode = Mode::SelectingTemplate;
         } else {
             self.enter_directory_browser(true);
@@ -69,11 +83,9 @@ impl<'a> App<'a> {
     }
 
     pub fn select_template(&mut self) {
-        if let Some(index) = self.template_list_state.selected() {
-            if let Some(path) = self.template_files.get(index).cloned() {
-                self.pending_template = Some(path);
-                self.mode = Mode::Naming;
-            }
+        if let Some(template) = self.selected_template() {
+            self.pending_template = Some(template.path.clone());
+            self.mode = Mode::Naming;
         }
     }
 
@@ -84,6 +96,14 @@ impl<'a> App<'a> {
             return;
         }
 
+        if let Some(template_path) = self.pending_template.take() {
+            let template = fs::read_to_string(template_path).unwrap_or_default();
+            let title = filename.trim_end_matches(".md").to_string();
+            self.filename_input.clear();
+            self.create_from_template(title, template);
+            return;
+        }
+
         let filename_with_ext = if filename.ends_with(".md") {
             filename
         } else {
@@ -91,16 +111,7 @@ impl<'a> App<'a> {
         };
 
         let new_path = self.root.join(filename_with_ext);
-        
-        if let Some(template_path) = self.pending_template.take() {
-            if let Ok(content) = fs::read_to_string(template_path) {
-                fs::write(&new_path, content).ok();
-            } else {
-                fs::write(&new_path, "").ok();
-            }
-        } else {
-            fs::write(&new_path, "").ok();
-        }
+        fs::write(&new_path, "").ok();
 
         self.load_files();
         let new_file_index = self.files.iter().position(|f| f == &new_path);
@@ -113,10 +124,16 @@ impl<'a> App<'a> {
         self.filename_input.clear();
     }
 
+    pub fn cancel_naming(&mut self) {
+        self.filename_input.clear();
+        self.pending_template = None;
+        self.mode = Mode::Normal;
+    }
+
     pub fn select_next(&mut self) {
         let (state, count) = match self.mode {
             Mode::ChangingDirectory | Mode::SelectingTemplateFolder => (&mut self.browser_state, self.browser_entries.len()),
-            Mode::SelectingTemplate => (&mut self.template_list_state, self.template_files.len()),
+            Mode::SelectingTemplate => (&mut self.template_list_state, self.filtered_templates.len()),
             Mode::Search => (&mut self.file_list_state, self.filtered_files.len()),
             _ => (&mut self.file_list_state, self.files.len()),
         };
@@ -137,7 +154,7 @@ impl<'a> App<'a> {
     pub fn select_previous(&mut self) {
         let (state, count) = match self.mode {
             Mode::ChangingDirectory | Mode::SelectingTemplateFolder => (&mut self.browser_state, self.browser_entries.len()),
-            Mode::SelectingTemplate => (&mut self.template_list_state, self.template_files.len()),
+            Mode::SelectingTemplate => (&mut self.template_list_state, self.filtered_templates.len()),
             Mode::Search => (&mut self.file_list_state, self.filtered_files.len()),
             _ => (&mut self.file_list_state, self.files.len()),
         };
@@ -160,23 +177,10 @@ impl<'a> App<'a> {
             let current_files = self.get_current_files();
             if let Some(path) = current_files.get(selected_index).cloned() {
                 if path.is_file() {
-                    let content = fs::read_to_string(&path).unwrap_or_default();
-                    let lines: Vec<String> = content.lines().map(String::from).collect();
-                    let mut editor = TextArea::new(lines);
-                    let block_style = Style::default().fg(self.color_scheme.primary_color());
-                    editor.set_block(
-                        Block::default()
-                            .title(" Editor (Press Esc to Save) ")
-                            .borders(Borders::ALL)
-                            .border_style(block_style)
-                            .border_type(BorderType::Double),
-                    );
-                    editor.set_style(Style::default().fg(self.color_scheme.primary_color()).bg(Color::Black));
-                    self.editor = Some(editor);
-                    self.editing_file_path = Some(path.clone());
-                    self.mode = Mode::Editing;
+                    self.open_buffer(&path);
                 } else {
                     self.root = path;
+                    self.refresh_vault();
                     self.load_files();
                     self.file_list_state.select(Some(0));
                     if self.mode == Mode::Search {
@@ -187,10 +191,34 @@ impl<'a> App<'a> {
         }
     }
 
-    pub fn stop_editing(&mut self) {
-        if let (Some(editor), Some(path)) = (self.editor.take(), self.editing_file_path.take()) {
-            fs::write(path, editor.lines().join("\n")).ok();
+    pub fn build_editor(&self, lines: Vec<String>) -> TextArea<'a> {
+        let mut editor = TextArea::new(lines);
+        editor.set_block(self.editor_block(true));
+        editor.set_style(Style::default().fg(self.color_scheme.primary_color()).bg(Color::Black));
+        editor.set_search_style(Style::default().fg(Color::Black).bg(self.color_scheme.secondary_color()));
+        editor
+    }
+
+    pub fn editor_block(&self, focused: bool) -> Block<'a> {
+        if focused {
+            let hint = self.keymap.key_hint(Mode::Editing, Action::StopEditing, true);
+            Block::default()
+                .title(hint.map(|keys| format!(" Editor (Press {} to Save) ", keys)).unwrap_or_else(|| " Editor ".to_string()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.color_scheme.primary_color()))
+                .border_type(BorderType::Double)
+        } else {
+            let hint = self.keymap.key_hint(Mode::Editing, Action::SwitchSplitFocus, true);
+            Block::default()
+                .title(hint.map(|keys| format!(" Viewing ({} to Focus) ", keys)).unwrap_or_else(|| " Viewing ".to_string()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .border_type(BorderType::Plain)
         }
+    }
+
+    pub fn stop_editing(&mut self) {
+        self.save_active_buffer();
         self.mode = Mode::Normal;
     }
 
@@ -249,6 +277,7 @@ impl<'a> App<'a> {
     pub fn navigate_up_directory(&mut self) {
         if let Some(parent) = self.root.parent() {
             self.root = parent.to_path_buf();
+            self.refresh_vault();
             self.save_config();
             self.load_files();
             self.file_list_state.select(Some(0));
@@ -259,6 +288,10 @@ impl<'a> App<'a> {
     }
 
     pub fn start_delete_confirmation(&mut self) {
+        if !self.marked.is_empty() {
+            self.request_batch(BatchAction::Delete);
+            return;
+        }
         if let Some(selected_index) = self.file_list_state.selected() {
             let current_files = self.get_current_files();
             if let Some(path) = current_files.get(selected_index).cloned() {
@@ -272,7 +305,9 @@ impl<'a> App<'a> {
 
     pub fn confirm_delete(&mut self) {
         if let Some(path) = self.operation_target_file.take() {
-            fs::remove_file(path).ok();
+            if fs::remove_file(&path).is_ok() {
+                self.discard_buffer(&path);
+            }
             self.load_files();
             if !self.files.is_empty() {
                 let new_selection = self.file_list_state.selected().unwrap_or(0).min(self.files.len() - 1);
@@ -296,12 +331,24 @@ impl<'a> App<'a> {
     }
 
     pub fn start_move_selection(&mut self) {
+        if !self.marked.is_empty() {
+            // Only stages every marked note may move to
+            let marked: Vec<PathBuf> = self.marked.iter().cloned().collect();
+            self.move_options = (0..self.stages.len())
+                .filter(|stage| marked.iter().all(|path| self.legal_moves(path).contains(stage)))
+                .collect();
+            self.operation_target_file = None;
+            self.move_selection_state.select(if self.move_options.is_empty() { None } else { Some(0) });
+            self.mode = Mode::SelectingMoveDestination;
+            return;
+        }
         if let Some(selected_index) = self.file_list_state.selected() {
             let current_files = self.get_current_files();
             if let Some(path) = current_files.get(selected_index).cloned() {
                 if path.is_file() {
+                    self.move_options = self.legal_moves(&path);
                     self.operation_target_file = Some(path);
-                    self.move_selection_state.select(Some(0));
+                    self.move_selection_state.select(if self.move_options.is_empty() { None } else { Some(0) });
                     self.mode = Mode::SelectingMoveDestination;
                 }
             }
@@ -309,7 +356,10 @@ impl<'a> App<'a> {
     }
 
     pub fn move_selection_next(&mut self) {
-        let count = self.move_destinations.len();
+        let count = self.move_options.len();
+        if count == 0 {
+            return;
+        }
         let i = match self.move_selection_state.selected() {
             Some(i) => {
                 if i >= count - 1 {
@@ -324,7 +374,10 @@ impl<'a> App<'a> {
     }
 
     pub fn move_selection_previous(&mut self) {
-        let count = self.move_destinations.len();
+        let count = self.move_options.len();
+        if count == 0 {
+            return;
+        }
         let i = match self.move_selection_state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -339,29 +392,22 @@ impl<'a> App<'a> {
     }
 
     pub fn execute_move(&mut self) {
-        if let (Some(path), Some(dest_index)) = (self.operation_target_file.take(), self.move_selection_state.selected()) {
-            if let Some(dest_path) = self.workflow_folders.get(dest_index) {
-                if let Some(filename) = path.file_name() {
-                    let new_path = dest_path.join(filename);
-                    fs::create_dir_all(&dest_path).ok();
-                    fs::rename(path, new_path).ok();
-                    self.load_files();
-                    if !self.files.is_empty() {
-                        let new_selection = self.file_list_state.selected().unwrap_or(0).min(self.files.len() - 1);
-                        self.file_list_state.select(Some(new_selection));
-                    } else {
-                        self.file_list_state.select(None);
-                    }
-                    if !self.search_input.is_empty() {
-                        self.update_filtered_files();
-                    }
-                }
+        self.mode = Mode::Normal;
+        if !self.marked.is_empty() {
+            if let Some(&stage) = self.move_selection_state.selected().and_then(|option| self.move_options.get(option)) {
+                self.request_batch(BatchAction::Move(stage));
+            }
+            return;
+        }
+        if let (Some(path), Some(option)) = (self.operation_target_file.take(), self.move_selection_state.selected()) {
+            if let Some(&stage) = self.move_options.get(option) {
+                self.move_to_stage(&path, stage);
             }
         }
-        self.mode = Mode::Normal;
     }
 
     pub fn enter_settings(&mut self) {
+        self.settings_section = SettingsSection::Themes;
         self.settings_selection_state.select(Some(0));
         self.mode = Mode::Settings;
     }
@@ -370,11 +416,18 @@ impl<'a> App<'a> {
         self.mode = Mode::Normal;
     }
 
+    fn settings_item_count(&self) -> usize {
+        match self.settings_section {
+            SettingsSection::Themes => ColorScheme::all_schemes().len(),
+            SettingsSection::Pipeline => self.stages.len(),
+        }
+    }
+
     pub fn settings_next(&mut self) {
-        let schemes = ColorScheme::all_schemes();
+        let count = self.settings_item_count();
         let i = match self.settings_selection_state.selected() {
             Some(i) => {
-                if i >= schemes.len() - 1 {
+                if i + 1 >= count {
                     0
                 } else {
                     i + 1
@@ -386,11 +439,11 @@ impl<'a> App<'a> {
     }
 
     pub fn settings_previous(&mut self) {
-        let schemes = ColorScheme::all_schemes();
+        let count = self.settings_item_count();
         let i = match self.settings_selection_state.selected() {
             Some(i) => {
                 if i == 0 {
-                    schemes.len() - 1
+                    count.saturating_sub(1)
                 } else {
                     i - 1
                 }
@@ -442,6 +495,8 @@ impl<'a> App<'a> {
             // Only rename if the new path is different and doesn't already exist
             if new_path != old_path && !new_path.exists() {
                 if fs::rename(&old_path, &new_path).is_ok() {
+                    self.retarget_buffer(&old_path, &new_path);
+                    self.retarget_bookmarks(&old_path, &new_path);
                     self.load_files();
                     
                     // Try to select the renamed file
@@ -470,7 +525,7 @@ impl<'a> App<'a> {
     }
 
     pub fn copy_file_to_clipboard(&self) {
-        if let Some(editor) = &self.editor {
+        if let Some(editor) = self.active_editor() {
             let content = editor.lines().join("\n");
             let _ = clipboard::copy_markdown_to_clipboard(&content);
         }
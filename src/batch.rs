@@ -0,0 +1,194 @@
+use chrono::Local;
+use std::{fs, path::PathBuf};
+
+use crate::app::App;
+use crate::clipboard;
+use crate::modes::Mode;
+
+/// An action waiting on the batch confirmation popup
+#[derive(Clone)]
+pub enum BatchAction {
+    Move(usize),
+    Delete,
+    Tag(String),
+    Export,
+    Copy,
+}
+
+/// Add `tag` to a note's frontmatter `tags`, in whichever list style it already uses
+pub fn add_tag(lines: &[String], tag: &str) -> Vec<String> {
+    let mut result = lines.to_vec();
+    let closing = (lines.first().map(String::as_str) == Some("---"))
+        .then(|| lines.iter().skip(1).position(|line| line == "---").map(|index| index + 1))
+        .flatten();
+    let Some(closing) = closing else {
+        let frontmatter = ["---".to_string(), format!("tags: [{}]", tag), "---".to_string()];
+        result.splice(0..0, frontmatter);
+        return result;
+    };
+
+    let Some(tags_line) = (1..closing).find(|&index| lines[index].starts_with("tags:")) else {
+        result.insert(closing, format!("tags: [{}]", tag));
+        return result;
+    };
+    let value = lines[tags_line]["tags:".len()..].trim();
+
+    if value.is_empty() {
+        // Block list: `tags:` followed by `  - tag` items
+        let end = (tags_line + 1..closing)
+            .find(|&index| !lines[index].trim_start().starts_with("- "))
+            .unwrap_or(closing);
+        let present = lines[tags_line + 1..end]
+            .iter()
+            .any(|line| line.trim_start().trim_start_matches("- ").trim() == tag);
+        if !present {
+            result.insert(end, format!("  - {}", tag));
+        }
+        return result;
+    }
+
+    let inner = value.trim_start_matches('[').trim_end_matches(']');
+    let mut tags: Vec<&str> = inner.split(',').map(str::trim).filter(|existing| !existing.is_empty()).collect();
+    if !tags.contains(&tag) {
+        tags.push(tag);
+    }
+    result[tags_line] = if value.starts_with('[') {
+        format!("tags: [{}]", tags.join(", "))
+    } else {
+        format!("tags: {}", tags.join(", "))
+    };
+    result
+}
+
+impl<'a> App<'a> {
+    pub fn toggle_mark(&mut self) {
+        let Some(path) = self.selected_file().filter(|path| path.is_file()).cloned() else {
+            return;
+        };
+        if !self.marked.remove(&path) {
+            self.marked.insert(path);
+        }
+        self.select_next();
+    }
+
+    /// Mark every file in the current list (the search results while searching), or clear them all if already marked
+    pub fn toggle_mark_all(&mut self) {
+        let files: Vec<PathBuf> = self.get_current_files().iter().filter(|path| path.is_file()).cloned().collect();
+        if files.iter().all(|path| self.marked.contains(path)) {
+            for path in &files {
+                self.marked.remove(path);
+            }
+        } else {
+            self.marked.extend(files);
+        }
+    }
+
+    /// Marked files if there are any, otherwise the highlighted one
+    pub fn batch_targets(&self) -> Vec<PathBuf> {
+        if self.marked.is_empty() {
+            self.selected_file().filter(|path| path.is_file()).cloned().into_iter().collect()
+        } else {
+            self.marked.iter().cloned().collect()
+        }
+    }
+
+    /// Confirm batch actions on marked files; act on a lone highlighted file straight away
+    pub fn request_batch(&mut self, action: BatchAction) {
+        if self.marked.is_empty() {
+            self.run_batch(action);
+        } else {
+            self.pending_batch = Some(action);
+            self.mode = Mode::ConfirmingBatch;
+        }
+    }
+
+    pub fn confirm_batch(&mut self) {
+        self.mode = Mode::Normal;
+        if let Some(action) = self.pending_batch.take() {
+            self.run_batch(action);
+        }
+    }
+
+    pub fn cancel_batch(&mut self) {
+        self.pending_batch = None;
+        self.mode = Mode::Normal;
+    }
+
+    fn run_batch(&mut self, action: BatchAction) {
+        let targets = self.batch_targets();
+        match action {
+            BatchAction::Move(stage) => {
+                for path in &targets {
+                    self.move_to_stage(path, stage);
+                }
+            }
+            BatchAction::Delete => {
+                for path in &targets {
+                    if fs::remove_file(path).is_ok() {
+                        self.discard_buffer(path);
+                    }
+                }
+            }
+            BatchAction::Tag(tag) => {
+                for path in &targets {
+                    self.rewrite_note(path, |lines| add_tag(lines, &tag));
+                }
+            }
+            BatchAction::Export => {
+                let export = self.root.join(format!("export-{}.md", Local::now().format("%Y-%m-%d-%H%M%S")));
+                fs::write(&export, self.joined_notes(&targets)).ok();
+            }
+            BatchAction::Copy => {
+                let _ = clipboard::copy_markdown_to_clipboard(&self.joined_notes(&targets));
+            }
+        }
+        self.marked.clear();
+
+        self.load_files();
+        let selection = self.file_list_state.selected().unwrap_or(0).min(self.files.len().saturating_sub(1));
+        self.file_list_state.select(if self.files.is_empty() { None } else { Some(selection) });
+        if !self.search_input.is_empty() {
+            self.update_filtered_files();
+        }
+    }
+
+    fn joined_notes(&self, paths: &[PathBuf]) -> String {
+        paths
+            .iter()
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .map(|content| content.trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n\n---\n\n")
+    }
+
+    pub fn start_tagging(&mut self) {
+        if !self.batch_targets().is_empty() {
+            self.filename_input.clear();
+            self.mode = Mode::Tagging;
+        }
+    }
+
+    pub fn cancel_tagging(&mut self) {
+        self.filename_input.clear();
+        self.mode = Mode::Normal;
+    }
+
+    pub fn submit_tag(&mut self) {
+        let tag = std::mem::take(&mut self.filename_input).trim().trim_start_matches('#').to_string();
+        self.mode = Mode::Normal;
+        if !tag.is_empty() {
+            self.request_batch(BatchAction::Tag(tag));
+        }
+    }
+
+    pub fn batch_description(&self) -> String {
+        match &self.pending_batch {
+            Some(BatchAction::Move(stage)) => format!("MOVE TO {}", self.stages[*stage].name.to_uppercase()),
+            Some(BatchAction::Delete) => "DELETE".to_string(),
+            Some(BatchAction::Tag(tag)) => format!("TAG #{}", tag),
+            Some(BatchAction::Export) => "EXPORT TO ONE NOTE".to_string(),
+            Some(BatchAction::Copy) => "COPY TO CLIPBOARD".to_string(),
+            None => String::new(),
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use ratatui::style::Color;
+use std::{fs, path::PathBuf};
+
+use crate::app::App;
+use crate::file_ops::IgnoreList;
+use crate::modes::Mode;
+use crate::workflow::INBOX;
+
+/// A board column: the root as the inbox, or one workflow stage
+pub struct BoardColumn {
+    pub title: String,
+    pub icon: String,
+    pub color: Color,
+    pub folder: PathBuf,
+    pub stage: Option<usize>,
+    pub wip_limit: Option<usize>,
+    pub cards: Vec<PathBuf>,
+    pub selected: usize,
+}
+
+impl BoardColumn {
+    pub fn over_limit(&self) -> bool {
+        self.wip_limit.is_some_and(|limit| self.cards.len() > limit)
+    }
+}
+
+pub struct BoardState {
+    pub columns: Vec<BoardColumn>,
+    pub column: usize,
+    pub notice: Option<String>,
+}
+
+fn load_cards(folder: &PathBuf, ignore: &IgnoreList) -> Vec<PathBuf> {
+    let mut cards: Vec<PathBuf> = fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && !path.file_name().unwrap_or_default().to_string_lossy().starts_with('.') && !ignore.matches(path))
+                .collect()
+        })
+        .unwrap_or_default();
+    cards.sort();
+    cards
+}
+
+impl<'a> App<'a> {
+    fn board_columns(&self) -> Vec<BoardColumn> {
+        let inbox = BoardColumn {
+            title: INBOX.to_string(),
+            icon: "✉".to_string(),
+            color: self.color_scheme.primary_color(),
+            folder: self.root.clone(),
+            stage: None,
+            wip_limit: None,
+            cards: load_cards(&self.root, &self.ignore),
+            selected: 0,
+        };
+        let stages = self.stages.iter().enumerate().map(|(index, stage)| BoardColumn {
+            title: stage.name.clone(),
+            icon: stage.icon.clone(),
+            color: self.stage_color(stage),
+            folder: stage.folder.clone(),
+            stage: Some(index),
+            wip_limit: stage.wip_limit,
+            cards: load_cards(&stage.folder, &self.ignore),
+            selected: 0,
+        });
+        std::iter::once(inbox).chain(stages).collect()
+    }
+
+    pub fn open_board(&mut self) {
+        self.board = Some(BoardState {
+            columns: self.board_columns(),
+            column: 0,
+            notice: None,
+        });
+        self.mode = Mode::Board;
+    }
+
+    pub fn close_board(&mut self) {
+        self.board = None;
+        self.mode = Mode::Normal;
+    }
+
+    /// Reload every column, keeping the cursor on `focus` if it's still on the board
+    fn refresh_board(&mut self, focus: Option<PathBuf>) {
+        let columns = self.board_columns();
+        let Some(board) = self.board.as_mut() else {
+            return;
+        };
+        let previous: Vec<usize> = board.columns.iter().map(|column| column.selected).collect();
+        board.columns = columns;
+        for (column, selected) in board.columns.iter_mut().zip(previous) {
+            column.selected = selected.min(column.cards.len().saturating_sub(1));
+        }
+        if let Some(focus) = focus {
+            for (index, column) in board.columns.iter_mut().enumerate() {
+                if let Some(card) = column.cards.iter().position(|card| *card == focus) {
+                    column.selected = card;
+                    board.column = index;
+                }
+            }
+        }
+        board.column = board.column.min(board.columns.len().saturating_sub(1));
+    }
+
+    pub fn board_selected_card(&self) -> Option<&PathBuf> {
+        let board = self.board.as_ref()?;
+        let column = board.columns.get(board.column)?;
+        column.cards.get(column.selected)
+    }
+
+    pub fn board_move_column_focus(&mut self, right: bool) {
+        if let Some(board) = self.board.as_mut() {
+            let count = board.columns.len();
+            board.column = if right { (board.column + 1) % count } else { (board.column + count - 1) % count };
+        }
+    }
+
+    pub fn board_move_card_focus(&mut self, down: bool) {
+        let Some(column) = self.board.as_mut().and_then(|board| board.columns.get_mut(board.column)) else {
+            return;
+        };
+        let count = column.cards.len();
+        if count > 0 {
+            column.selected = if down { (column.selected + 1) % count } else { (column.selected + count - 1) % count };
+        }
+    }
+
+    /// Move the selected card to the neighbouring column, if the pipeline allows it
+    pub fn board_shift_card(&mut self, right: bool) {
+        let (Some(card), Some(board)) = (self.board_selected_card().cloned(), self.board.as_ref()) else {
+            return;
+        };
+        let target = if right { board.column + 1 } else { board.column.wrapping_sub(1) };
+        let Some(column) = board.columns.get(target) else {
+            return;
+        };
+        let (folder, stage, title) = (column.folder.clone(), column.stage, column.title.clone());
+
+        let allowed = match stage {
+            Some(stage) => self.legal_moves(&card).contains(&stage),
+            None => self.may_move_to_inbox(&card),
+        };
+        let name = card.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let notice = if !allowed {
+            Some(format!("{} can't move to {}", name, title))
+        } else if folder.join(&name).exists() {
+            Some(format!("{} already has a note called {}", title, name))
+        } else if let Some(new_path) = match stage {
+            Some(stage) => self.move_to_stage(&card, stage),
+            None => self.move_note(&card, &folder),
+        } {
+            self.refresh_board(Some(new_path));
+            None
+        } else {
+            Some(format!("{} stayed put", name))
+        };
+        if let Some(board) = self.board.as_mut() {
+            board.notice = notice;
+        }
+    }
+
+    pub fn board_open_card(&mut self) {
+        if let Some(card) = self.board_selected_card().cloned() {
+            self.board = None;
+            self.open_buffer(&card);
+        }
+    }
+}
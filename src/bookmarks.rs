@@ -0,0 +1,228 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::widgets::ListState;
+use toml_edit::{Array, Table};
+
+use crate::app::App;
+use crate::config::{set, Section};
+use crate::modes::Mode;
+
+pub const MAX_RECENT: usize = 15;
+
+/// Pinned notes and folders plus the most recently opened notes, persisted in the config
+#[derive(Default)]
+pub struct Bookmarks {
+    pub pinned: Vec<PathBuf>,
+    pub recent: Vec<PathBuf>,
+}
+
+pub struct SwitcherEntry {
+    pub path: PathBuf,
+    pub pinned: bool,
+}
+
+pub struct SwitcherState {
+    pub filter: String,
+    pub entries: Vec<SwitcherEntry>,
+    pub list_state: ListState,
+}
+
+impl Bookmarks {
+    pub fn from_config(config: &Section, errors: &mut Vec<String>) -> Self {
+        config.check_keys(&["pinned", "recent"], errors);
+        let pinned = config.strings("pinned", errors).unwrap_or_default().into_iter().map(PathBuf::from).collect();
+        let mut recent: Vec<PathBuf> = config.strings("recent", errors).unwrap_or_default().into_iter().map(PathBuf::from).collect();
+        recent.truncate(MAX_RECENT);
+        Self { pinned, recent }
+    }
+
+    pub fn write_config(&self, config: &mut Table) {
+        for (key, paths) in [("pinned", &self.pinned), ("recent", &self.recent)] {
+            set(config, key, paths.iter().map(|path| path.to_string_lossy().to_string()).collect::<Array>());
+        }
+    }
+
+    /// Pinned entries first, then recent notes that aren't pinned, skipping anything that no longer exists
+    pub fn entries(&self) -> Vec<SwitcherEntry> {
+        let pinned = self.pinned.iter().map(|path| SwitcherEntry { path: path.clone(), pinned: true });
+        let recent = self
+            .recent
+            .iter()
+            .filter(|path| !self.pinned.contains(path))
+            .map(|path| SwitcherEntry { path: path.clone(), pinned: false });
+        pinned.chain(recent).filter(|entry| entry.path.exists()).collect()
+    }
+}
+
+/// An absolute path that still works once the file itself has been renamed away
+pub fn absolute_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = fs::canonicalize(path) {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => fs::canonicalize(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// `path` with its `old` prefix swapped for `new`, if it lies at or under `old`
+fn retarget(path: &Path, old: &Path, new: &Path) -> Option<PathBuf> {
+    path.strip_prefix(old).ok().map(|rest| if rest.as_os_str().is_empty() { new.to_path_buf() } else { new.join(rest) })
+}
+
+impl<'a> App<'a> {
+    pub fn record_recent(&mut self, path: &Path) {
+        let path = absolute_path(path);
+        let recent = &mut self.bookmarks.recent;
+        if recent.first() == Some(&path) {
+            return;
+        }
+        recent.retain(|existing| *existing != path);
+        recent.insert(0, path);
+        recent.truncate(MAX_RECENT);
+        self.save_config();
+    }
+
+    /// Pin or unpin the highlighted note or folder
+    pub fn toggle_pin(&mut self) {
+        let Some(path) = self.selected_file().map(|path| absolute_path(path)) else {
+            return;
+        };
+        self.flip_pin(path);
+    }
+
+    fn flip_pin(&mut self, path: PathBuf) {
+        if let Some(index) = self.bookmarks.pinned.iter().position(|pinned| *pinned == path) {
+            self.bookmarks.pinned.remove(index);
+        } else {
+            self.bookmarks.pinned.push(path);
+        }
+        self.save_config();
+    }
+
+    /// Follow a rename or move so pins and recent entries keep pointing at the note
+    pub fn retarget_bookmarks(&mut self, old_path: &Path, new_path: &Path) {
+        let old_path = absolute_path(old_path);
+        let new_path = absolute_path(new_path);
+        let mut changed = false;
+        for path in self.bookmarks.pinned.iter_mut().chain(self.bookmarks.recent.iter_mut()) {
+            if let Some(updated) = retarget(path, &old_path, &new_path) {
+                *path = updated;
+                changed = true;
+            }
+        }
+        if changed {
+            self.save_config();
+        }
+    }
+
+    pub fn open_switcher(&mut self) {
+        self.switcher = Some(SwitcherState {
+            filter: String::new(),
+            entries: Vec::new(),
+            list_state: ListState::default(),
+        });
+        self.update_switcher();
+        self.mode = Mode::Switcher;
+    }
+
+    pub fn close_switcher(&mut self) {
+        self.switcher = None;
+        self.mode = Mode::Normal;
+    }
+
+    fn update_switcher(&mut self) {
+        let entries = self.bookmarks.entries();
+        let Some(switcher) = self.switcher.as_mut() else {
+            return;
+        };
+        switcher.entries = if switcher.filter.is_empty() {
+            entries
+        } else {
+            let mut scored: Vec<(i64, SwitcherEntry)> = entries
+                .into_iter()
+                .filter_map(|entry| {
+                    let score = self.fuzzy_matcher.fuzzy_match(&entry.path.to_string_lossy(), &switcher.filter)?;
+                    Some((score, entry))
+                })
+                .collect();
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            scored.into_iter().map(|(_, entry)| entry).collect()
+        };
+        switcher.list_state.select(if switcher.entries.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn switcher_input(&mut self, c: char) {
+        if let Some(switcher) = self.switcher.as_mut() {
+            switcher.filter.push(c);
+        }
+        self.update_switcher();
+    }
+
+    pub fn switcher_backspace(&mut self) {
+        if let Some(switcher) = self.switcher.as_mut() {
+            switcher.filter.pop();
+        }
+        self.update_switcher();
+    }
+
+    pub fn switcher_select(&mut self, forward: bool) {
+        let Some(switcher) = self.switcher.as_mut() else {
+            return;
+        };
+        let count = switcher.entries.len();
+        if count == 0 {
+            return;
+        }
+        let current = switcher.list_state.selected().unwrap_or(0);
+        let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
+        switcher.list_state.select(Some(next));
+    }
+
+    /// Pin or unpin the highlighted switcher entry
+    pub fn switcher_toggle_pin(&mut self) {
+        let Some(path) = self
+            .switcher
+            .as_ref()
+            .and_then(|switcher| switcher.entries.get(switcher.list_state.selected()?))
+            .map(|entry| entry.path.clone())
+        else {
+            return;
+        };
+        self.flip_pin(path);
+        let selected = self.switcher.as_ref().and_then(|switcher| switcher.list_state.selected());
+        self.update_switcher();
+        if let Some(switcher) = self.switcher.as_mut() {
+            let last = switcher.entries.len().checked_sub(1);
+            switcher.list_state.select(selected.zip(last).map(|(selected, last)| selected.min(last)));
+        }
+    }
+
+    /// Open the highlighted note, or make the highlighted folder the file list root
+    pub fn switcher_open(&mut self) {
+        let Some(path) = self
+            .switcher
+            .as_ref()
+            .and_then(|switcher| switcher.entries.get(switcher.list_state.selected()?))
+            .map(|entry| entry.path.clone())
+        else {
+            return;
+        };
+        self.close_switcher();
+        if path.is_dir() {
+            self.root = path;
+            self.refresh_vault();
+            self.save_config();
+            self.load_files();
+            self.file_list_state.select(if self.files.is_empty() { None } else { Some(0) });
+        } else {
+            if let Some(index) = self.files.iter().position(|file| absolute_path(file) == path) {
+                self.file_list_state.select(Some(index));
+            }
+            self.open_buffer(&path);
+        }
+    }
+}
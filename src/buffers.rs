@@ -0,0 +1,256 @@
+use std::{fs, path::{Path, PathBuf}};
+use tui_textarea::{CursorMove, TextArea};
+
+use crate::app::App;
+use crate::find::{edit_to_match, ReplaceSnapshot};
+use crate::highlight::HighlightCache;
+use crate::modes::Mode;
+use crate::split::SplitState;
+
+/// A note open in the editor, kept alive while other notes are edited
+pub struct Buffer<'a> {
+    pub path: PathBuf,
+    pub editor: TextArea<'a>,
+    pub saved_lines: Vec<String>,
+    pub replace_history: Vec<ReplaceSnapshot>,
+    pub highlight: HighlightCache,
+    pub scroll_top: usize,
+    pub scroll_left: usize,
+    pub view_height: u16,
+}
+
+impl<'a> Buffer<'a> {
+    pub fn is_modified(&self) -> bool {
+        self.editor.lines() != self.saved_lines.as_slice()
+    }
+
+    pub fn title(&self) -> String {
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        if self.is_modified() {
+            format!("{} ●", name)
+        } else {
+            name.into_owned()
+        }
+    }
+
+    /// Move the cursor a screenful up or down, since the editor view owns scrolling
+    pub fn page(&mut self, down: bool) {
+        let (row, col) = self.editor.cursor();
+        let page = self.view_height.max(1) as usize;
+        let target = if down { row + page } else { row.saturating_sub(page) };
+        let target = target.min(self.editor.lines().len().saturating_sub(1));
+        self.editor.move_cursor(CursorMove::Jump(target as u16, col as u16));
+    }
+
+    /// Edit the buffer to hold `lines`, as one undoable step
+    pub fn replace_lines(&mut self, lines: Vec<String>, cursor: (usize, usize)) {
+        let steps = edit_to_match(&mut self.editor, &lines);
+        if steps > 0 {
+            self.replace_history.push(ReplaceSnapshot { lines: self.editor.lines().to_vec(), steps });
+        }
+        self.editor.move_cursor(CursorMove::Jump(cursor.0 as u16, cursor.1 as u16));
+    }
+
+    pub fn save(&mut self) {
+        if fs::write(&self.path, self.editor.lines().join("\n")).is_ok() {
+            self.saved_lines = self.editor.lines().to_vec();
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn active_buffer(&self) -> Option<&Buffer<'a>> {
+        self.buffers.get(self.active_buffer)
+    }
+
+    pub fn active_buffer_mut(&mut self) -> Option<&mut Buffer<'a>> {
+        self.buffers.get_mut(self.active_buffer)
+    }
+
+    pub fn active_editor(&self) -> Option<&TextArea<'a>> {
+        self.active_buffer().map(|buffer| &buffer.editor)
+    }
+
+    pub fn active_editor_mut(&mut self) -> Option<&mut TextArea<'a>> {
+        self.active_buffer_mut().map(|buffer| &mut buffer.editor)
+    }
+
+    pub fn has_unsaved_buffers(&self) -> bool {
+        self.buffers.iter().any(|buffer| buffer.is_modified())
+    }
+
+    /// Switch to the buffer for `path`, opening it if it isn't open yet
+    pub fn open_buffer(&mut self, path: &Path) {
+        if let Some(index) = self.buffers.iter().position(|buffer| buffer.path == path) {
+            self.active_buffer = index;
+        } else {
+            let content = fs::read_to_string(path).unwrap_or_default();
+            let lines: Vec<String> = content.lines().map(String::from).collect();
+            self.buffers.push(Buffer {
+                path: path.to_path_buf(),
+                editor: self.build_editor(lines.clone()),
+                saved_lines: lines,
+                replace_history: Vec::new(),
+                highlight: HighlightCache::default(),
+                scroll_top: 0,
+                scroll_left: 0,
+                view_height: 0,
+            });
+            self.active_buffer = self.buffers.len() - 1;
+        }
+        self.record_recent(path);
+        self.refresh_editor_blocks();
+        self.mode = Mode::Editing;
+    }
+
+    pub fn resume_editing(&mut self) {
+        if !self.buffers.is_empty() {
+            self.mode = Mode::Editing;
+        }
+    }
+
+    pub fn editor_page_down(&mut self) {
+        if let Some(buffer) = self.active_buffer_mut() {
+            buffer.page(true);
+        }
+    }
+
+    pub fn editor_page_up(&mut self) {
+        if let Some(buffer) = self.active_buffer_mut() {
+            buffer.page(false);
+        }
+    }
+
+    pub fn save_active_buffer(&mut self) {
+        if let Some(buffer) = self.active_buffer_mut() {
+            buffer.save();
+        }
+    }
+
+    pub fn save_all_buffers(&mut self) {
+        for buffer in &mut self.buffers {
+            if buffer.is_modified() {
+                buffer.save();
+            }
+        }
+    }
+
+    pub fn next_buffer(&mut self) {
+        if !self.buffers.is_empty() {
+            self.focus_buffer((self.active_buffer + 1) % self.buffers.len());
+        }
+    }
+
+    pub fn previous_buffer(&mut self) {
+        if !self.buffers.is_empty() {
+            self.focus_buffer((self.active_buffer + self.buffers.len() - 1) % self.buffers.len());
+        }
+    }
+
+    /// Show `index` in the focused pane, swapping panes if the other split pane already shows it
+    fn focus_buffer(&mut self, index: usize) {
+        let previous = self.active_buffer;
+        self.active_buffer = index;
+        if let Some(split) = self.split.as_mut() {
+            if split.other_buffer == index {
+                split.other_buffer = previous;
+            }
+        }
+        self.refresh_editor_blocks();
+    }
+
+    /// Remove a buffer, keeping the active index and any split pointing at the right buffers
+    fn remove_buffer(&mut self, index: usize) -> Buffer<'a> {
+        let buffer = self.buffers.remove(index);
+        let shift = |i: usize| if i > index { i - 1 } else { i };
+
+        match self.split.take() {
+            Some(split) if index == self.active_buffer => {
+                self.active_buffer = shift(split.other_buffer);
+            }
+            Some(split) if index != split.other_buffer => {
+                self.active_buffer = shift(self.active_buffer);
+                self.split = Some(SplitState {
+                    other_buffer: shift(split.other_buffer),
+                    ..split
+                });
+            }
+            _ => self.active_buffer = shift(self.active_buffer),
+        }
+        self.active_buffer = self.active_buffer.min(self.buffers.len().saturating_sub(1));
+        self.refresh_editor_blocks();
+        buffer
+    }
+
+    /// Save and close the active buffer, leaving the editor once none remain
+    pub fn close_active_buffer(&mut self) {
+        if self.active_buffer >= self.buffers.len() {
+            return;
+        }
+        let mut buffer = self.remove_buffer(self.active_buffer);
+        buffer.save();
+        if self.buffers.is_empty() {
+            self.mode = Mode::Normal;
+        }
+    }
+
+    /// Point any buffer editing `old_path` at `new_path` after a rename or move
+    pub fn retarget_buffer(&mut self, old_path: &Path, new_path: &Path) {
+        for buffer in &mut self.buffers {
+            if buffer.path == old_path {
+                buffer.path = new_path.to_path_buf();
+            }
+        }
+    }
+
+    /// Drop the buffer for a file that no longer exists
+    pub fn discard_buffer(&mut self, path: &Path) {
+        if let Some(index) = self.buffers.iter().position(|buffer| buffer.path == path) {
+            self.remove_buffer(index);
+        }
+    }
+
+    /// Rewrite a note on disk, applying the same change to an open buffer so unsaved edits survive
+    pub fn rewrite_note(&mut self, path: &Path, change: impl Fn(&[String]) -> Vec<String>) {
+        let Ok(content) = fs::read_to_string(path) else {
+            return;
+        };
+        let disk_lines: Vec<String> = content.lines().map(String::from).collect();
+        let updated = change(&disk_lines);
+        if updated == disk_lines {
+            return;
+        }
+        let mut new_content = updated.join("\n");
+        if content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        fs::write(path, new_content).ok();
+
+        let Some(index) = self.buffers.iter().position(|buffer| buffer.path == path) else {
+            return;
+        };
+        let buffer_lines = change(self.buffers[index].editor.lines());
+        let inserted = buffer_lines.len().saturating_sub(self.buffers[index].editor.lines().len());
+        let buffer = &mut self.buffers[index];
+        let (row, col) = buffer.editor.cursor();
+        buffer.replace_lines(buffer_lines, (row + inserted, col));
+        buffer.saved_lines = updated;
+    }
+
+    pub fn request_quit(&mut self) {
+        if self.has_unsaved_buffers() {
+            self.mode = Mode::ConfirmingQuit;
+        } else {
+            self.should_quit = true;
+        }
+    }
+
+    pub fn save_all_and_quit(&mut self) {
+        self.save_all_buffers();
+        self.should_quit = true;
+    }
+
+    pub fn discard_and_quit(&mut self) {
+        self.should_quit = true;
+    }
+}
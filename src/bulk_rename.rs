@@ -0,0 +1,330 @@
+use chrono::{DateTime, Local, NaiveDate};
+use regex::Regex;
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+
+use crate::app::App;
+use crate::modes::Mode;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum CaseTransform {
+    Keep,
+    Lower,
+    Upper,
+    Title,
+    Kebab,
+}
+
+impl CaseTransform {
+    pub fn next(self) -> Self {
+        match self {
+            CaseTransform::Keep => CaseTransform::Lower,
+            CaseTransform::Lower => CaseTransform::Upper,
+            CaseTransform::Upper => CaseTransform::Title,
+            CaseTransform::Title => CaseTransform::Kebab,
+            CaseTransform::Kebab => CaseTransform::Keep,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CaseTransform::Keep => "keep",
+            CaseTransform::Lower => "lower",
+            CaseTransform::Upper => "UPPER",
+            CaseTransform::Title => "Title",
+            CaseTransform::Kebab => "kebab-case",
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            CaseTransform::Keep => text.to_string(),
+            CaseTransform::Lower => text.to_lowercase(),
+            CaseTransform::Upper => text.to_uppercase(),
+            CaseTransform::Title => text
+                .split(' ')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" "),
+            CaseTransform::Kebab => crate::templates::slugify(text),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum DatePrefix {
+    None,
+    Modified,
+    Frontmatter,
+}
+
+impl DatePrefix {
+    pub fn next(self) -> Self {
+        match self {
+            DatePrefix::None => DatePrefix::Modified,
+            DatePrefix::Modified => DatePrefix::Frontmatter,
+            DatePrefix::Frontmatter => DatePrefix::None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DatePrefix::None => "none",
+            DatePrefix::Modified => "modified time",
+            DatePrefix::Frontmatter => "frontmatter date",
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum RenameField {
+    Find,
+    Replace,
+}
+
+impl RenameField {
+    pub fn other(self) -> Self {
+        match self {
+            RenameField::Find => RenameField::Replace,
+            RenameField::Replace => RenameField::Find,
+        }
+    }
+}
+
+/// One row of the old → new preview
+pub struct RenamePreview {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub collision: bool,
+}
+
+pub struct BulkRenameState {
+    pub files: Vec<PathBuf>,
+    pub find: String,
+    pub replace: String,
+    pub field: RenameField,
+    pub case: CaseTransform,
+    pub date_prefix: DatePrefix,
+    pub numbering: bool,
+    pub preview: Vec<RenamePreview>,
+    pub error: Option<String>,
+}
+
+impl BulkRenameState {
+    pub fn has_collisions(&self) -> bool {
+        self.preview.iter().any(|row| row.collision)
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.preview.iter().any(|row| row.from != row.to)
+    }
+}
+
+fn modified_date(path: &Path) -> Option<NaiveDate> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Local>::from(modified).date_naive())
+}
+
+/// The `date:` value in a note's frontmatter
+fn frontmatter_date(path: &Path) -> Option<NaiveDate> {
+    let content = fs::read_to_string(path).ok()?;
+    let frontmatter = content.strip_prefix("---\n")?.split("\n---").next()?;
+    let value = frontmatter.lines().find_map(|line| line.strip_prefix("date:"))?;
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Work out every new name, flagging duplicates within the batch and clashes with other files
+fn build_preview(state: &BulkRenameState, pattern: Option<&Regex>) -> Vec<RenamePreview> {
+    let width = state.files.len().to_string().len().max(2);
+    let mut rows: Vec<RenamePreview> = state
+        .files
+        .iter()
+        .enumerate()
+        .map(|(index, path)| {
+            let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+            let mut name = match pattern {
+                Some(pattern) => pattern.replace_all(&stem, state.replace.as_str()).into_owned(),
+                None => stem,
+            };
+            name = state.case.apply(&name);
+
+            let date = match state.date_prefix {
+                DatePrefix::None => None,
+                DatePrefix::Modified => modified_date(path),
+                DatePrefix::Frontmatter => frontmatter_date(path),
+            };
+            if let Some(date) = date {
+                let prefix = date.format("%Y-%m-%d").to_string();
+                if !name.starts_with(&prefix) {
+                    name = format!("{}-{}", prefix, name);
+                }
+            }
+            if state.numbering {
+                name = format!("{:0width$}-{}", index + 1, name, width = width);
+            }
+
+            let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+            RenamePreview {
+                from: path.clone(),
+                to: path.with_file_name(format!("{}{}", name, extension)),
+                collision: false,
+            }
+        })
+        .collect();
+
+    let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+    for row in &rows {
+        *counts.entry(row.to.clone()).or_default() += 1;
+    }
+    for row in &mut rows {
+        let taken_outside = row.to != row.from && row.to.exists() && !state.files.contains(&row.to);
+        row.collision = counts[&row.to] > 1 || taken_outside || row.to.file_stem().is_none_or(|stem| stem.is_empty());
+    }
+    rows
+}
+
+impl<'a> App<'a> {
+    /// Bulk rename the marked files, or every file in the current list if none are marked
+    pub fn start_bulk_rename(&mut self) {
+        let files: Vec<PathBuf> = if self.marked.is_empty() {
+            self.get_current_files().iter().filter(|path| path.is_file()).cloned().collect()
+        } else {
+            self.marked.iter().cloned().collect()
+        };
+        if files.is_empty() {
+            return;
+        }
+        let mut state = BulkRenameState {
+            files,
+            find: String::new(),
+            replace: String::new(),
+            field: RenameField::Find,
+            case: CaseTransform::Keep,
+            date_prefix: DatePrefix::None,
+            numbering: false,
+            preview: Vec::new(),
+            error: None,
+        };
+        state.preview = build_preview(&state, None);
+        self.bulk_rename = Some(state);
+        self.mode = Mode::BulkRename;
+    }
+
+    pub fn refresh_bulk_rename(&mut self) {
+        let Some(state) = self.bulk_rename.as_mut() else {
+            return;
+        };
+        let pattern = if state.find.is_empty() {
+            Ok(None)
+        } else {
+            Regex::new(&state.find).map(Some)
+        };
+        match pattern {
+            Ok(pattern) => {
+                state.error = None;
+                state.preview = build_preview(state, pattern.as_ref());
+            }
+            Err(error) => state.error = Some(error.to_string()),
+        }
+    }
+
+    pub fn bulk_rename_edit(&mut self, edit: impl FnOnce(&mut BulkRenameState)) {
+        if let Some(state) = self.bulk_rename.as_mut() {
+            edit(state);
+        }
+        self.refresh_bulk_rename();
+    }
+
+    pub fn bulk_rename_input(&mut self, c: char) {
+        self.bulk_rename_edit(|state| match state.field {
+            RenameField::Find => state.find.push(c),
+            RenameField::Replace => state.replace.push(c),
+        });
+    }
+
+    pub fn bulk_rename_backspace(&mut self) {
+        self.bulk_rename_edit(|state| {
+            match state.field {
+                RenameField::Find => state.find.pop(),
+                RenameField::Replace => state.replace.pop(),
+            };
+        });
+    }
+
+    pub fn cancel_bulk_rename(&mut self) {
+        self.bulk_rename = None;
+        self.mode = Mode::Normal;
+    }
+
+    /// Rename everything via temporary names first, so swaps and chains within the batch can't clobber each other
+    pub fn apply_bulk_rename(&mut self) {
+        let Some(state) = self.bulk_rename.as_ref() else {
+            return;
+        };
+        if state.error.is_some() || state.has_collisions() || !state.has_changes() {
+            return;
+        }
+        let changes: Vec<(PathBuf, PathBuf)> = state
+            .preview
+            .iter()
+            .filter(|row| row.from != row.to)
+            .map(|row| (row.from.clone(), row.to.clone()))
+            .collect();
+
+        let mut staged = Vec::new();
+        for (index, (from, to)) in changes.iter().enumerate() {
+            let temporary = from.with_file_name(format!(".nostromo-rename-{}", index));
+            let result = if temporary.exists() {
+                Err(format!("{} is in the way", temporary.display()))
+            } else {
+                fs::rename(from, &temporary).map_err(|error| error.to_string())
+            };
+            if let Err(error) = result {
+                // Put back everything staged so far; their original names are still free
+                for (from, temporary, _) in staged.into_iter().rev() {
+                    fs::rename(temporary, from).ok();
+                }
+                if let Some(state) = self.bulk_rename.as_mut() {
+                    state.error = Some(format!("Couldn't rename {}: {}. Nothing was renamed", from.display(), error));
+                }
+                return;
+            }
+            staged.push((from, temporary, to));
+        }
+
+        let mut failed = Vec::new();
+        for (from, temporary, to) in staged {
+            if !to.exists() && fs::rename(&temporary, to).is_ok() {
+                self.retarget_buffer(from, to);
+                self.retarget_bookmarks(from, to);
+                if self.marked.remove(from) {
+                    self.marked.insert(to.clone());
+                }
+            } else if !from.exists() && fs::rename(&temporary, from).is_ok() {
+                failed.push(from.file_name().unwrap_or_default().to_string_lossy().into_owned());
+            } else {
+                failed.push(format!("{} (left as {})", from.display(), temporary.display()));
+            }
+        }
+        if !failed.is_empty() {
+            self.load_files();
+            if let Some(state) = self.bulk_rename.as_mut() {
+                state.error = Some(format!("Couldn't rename {}; the rest were renamed", failed.join(", ")));
+            }
+            return;
+        }
+
+        self.bulk_rename = None;
+        self.mode = Mode::Normal;
+        self.load_files();
+        if !self.search_input.is_empty() {
+            self.update_filtered_files();
+        }
+    }
+}
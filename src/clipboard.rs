@@ -168,3 +168,7 @@ pub fn copy_markdown_to_clipboard(markdown_content: &str) -> Result<(), Box<dyn
     
     Ok(())
 }
+
+pub fn read_clipboard_text() -> Option<String> {
+    Clipboard::new().ok()?.get_text().ok()
+}
@@ -1,80 +1,514 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, io, path::{Path, PathBuf}};
 use dirs;
+use toml_edit::{Array, DocumentMut, Item, Table, TableLike, Value};
 
-use crate::ui::themes::ColorScheme;
+use crate::app::App;
+use crate::bookmarks::Bookmarks;
 use crate::constants::DEFAULT_MOVE_DESTINATIONS;
+use crate::editor_options::EditorOptions;
+use crate::keymap::Keymap;
+use crate::modes::Mode;
+use crate::file_sort::FileListOptions;
+use crate::vault::{read_trusted_vaults, read_vaults, write_trusted_vaults, write_vault_sessions, LayerSettings, NamedVault};
+
+/// Layout version written as `version`; bump it and upgrade older documents in `load_document` when the layout changes
+pub const CONFIG_VERSION: i64 = 1;
+
+/// Everything read from config.toml; anything unreadable keeps its default and is described in `errors`
+pub struct Config {
+    pub root: PathBuf,
+    pub daily_template: Option<PathBuf>,
+    pub history_frontmatter: bool,
+    /// The settings a vault can override, as the global config sets them
+    pub settings: LayerSettings,
+    pub editor_options: EditorOptions,
+    pub file_list_options: FileListOptions,
+    pub bookmarks: Bookmarks,
+    pub vaults: Vec<NamedVault>,
+    /// Vaults whose stage hooks the user has agreed to run
+    pub trusted_vaults: BTreeMap<PathBuf, String>,
+    pub keymap: Keymap,
+    pub errors: Vec<String>,
+}
+
+/// A table being read, named by its dotted path so errors can point at the setting
+pub struct Section<'t> {
+    pub name: String,
+    table: Option<&'t dyn TableLike>,
+}
 
 pub fn config_path() -> Option<PathBuf> {
-    dirs::home_dir().map(|mut path| {
-        path.push(".nostromo-notes.conf");
-        path
-    })
-}
-
-pub fn load_config() -> (PathBuf, Option<PathBuf>, ColorScheme, Vec<PathBuf>) {
-    let mut root = PathBuf::from(".");
-    let mut template_root = None;
-    let mut color_scheme = ColorScheme::Green;
-    let mut workflow_folders = Vec::new();
-
-    if let Some(path) = config_path() {
-        if let Ok(content) = fs::read_to_string(path) {
-            let config: HashMap<_, _> = content
-                .lines()
-                .filter_map(|line| line.split_once('='))
-                .collect();
-            
-            if let Some(root_str) = config.get("root") {
-                root = PathBuf::from(root_str.trim());
-            }
-            
-            if let Some(tmpl_str) = config.get("template_root") {
-                let path = PathBuf::from(tmpl_str.trim());
-                if path.is_dir() {
-                    template_root = Some(path);
-                }
-            }
-            
-            if let Some(color_str) = config.get("color_scheme") {
-                color_scheme = ColorScheme::from_string(color_str.trim());
-            }
-            
-            // Load workflow folders
-            for (i, destination) in DEFAULT_MOVE_DESTINATIONS.iter().enumerate() {
-                let key = format!("workflow_{}", i);
-                if let Some(folder_str) = config.get(key.as_str()) {
-                    workflow_folders.push(PathBuf::from(folder_str.trim()));
-                } else {
-                    // Default: relative to root
-                    workflow_folders.push(root.join(destination));
-                }
+    dirs::config_dir().map(|path| path.join("nostromo-notes").join("config.toml"))
+}
+
+/// The key=value file used before config.toml, imported on first run
+fn legacy_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|path| path.join(".nostromo-notes.conf"))
+}
+
+impl<'t> Section<'t> {
+    pub fn new(table: &'t dyn TableLike) -> Self {
+        Self { name: String::new(), table: Some(table) }
+    }
+
+    pub fn key_path(&self, key: &str) -> String {
+        if self.name.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.name, key)
+        }
+    }
+
+    fn item(&self, key: &str) -> Option<&'t Item> {
+        self.table?.get(key)
+    }
+
+    /// `found` if the setting had the right type, otherwise an error saying what was expected
+    fn typed<T>(&self, key: &str, found: Option<T>, expected: &str, errors: &mut Vec<String>) -> Option<T> {
+        if found.is_none() {
+            errors.push(format!("{}: expected {}", self.key_path(key), expected));
+        }
+        found
+    }
+
+    /// Keys in the order they appear in the file
+    pub fn keys(&self) -> Vec<&'t str> {
+        self.table.map(|table| table.iter().map(|(key, _)| key).collect()).unwrap_or_default()
+    }
+
+    pub fn check_keys(&self, known: &[&str], errors: &mut Vec<String>) {
+        for key in self.keys().into_iter().filter(|key| !known.contains(key)) {
+            errors.push(format!("{}: unknown setting", self.key_path(key)));
+        }
+    }
+
+    pub fn str(&self, key: &str, errors: &mut Vec<String>) -> Option<&'t str> {
+        let item = self.item(key)?;
+        self.typed(key, item.as_str(), "a string", errors)
+    }
+
+    pub fn path(&self, key: &str, errors: &mut Vec<String>) -> Option<PathBuf> {
+        self.str(key, errors).filter(|path| !path.is_empty()).map(PathBuf::from)
+    }
+
+    pub fn bool(&self, key: &str, errors: &mut Vec<String>) -> Option<bool> {
+        let item = self.item(key)?;
+        self.typed(key, item.as_bool(), "true or false", errors)
+    }
+
+    pub fn positive(&self, key: &str, errors: &mut Vec<String>) -> Option<usize> {
+        let item = self.item(key)?;
+        let found = item.as_integer().filter(|number| *number > 0).map(|number| number as usize);
+        self.typed(key, found, "a positive number", errors)
+    }
+
+    /// A string that must be one of `choices`
+    pub fn choice(&self, key: &str, choices: &[&str], errors: &mut Vec<String>) -> Option<&'t str> {
+        let item = self.item(key)?;
+        let found = item.as_str().filter(|value| choices.contains(value));
+        self.typed(key, found, &format!("one of {}", choices.join(", ")), errors)
+    }
+
+    /// An array of strings; a lone string counts as a one-element array
+    pub fn strings(&self, key: &str, errors: &mut Vec<String>) -> Option<Vec<&'t str>> {
+        let item = self.item(key)?;
+        let found = match item.as_str() {
+            Some(value) => Some(vec![value]),
+            None => item.as_array().and_then(|array| array.iter().map(Value::as_str).collect()),
+        };
+        self.typed(key, found, "a list of strings", errors)
+    }
+
+    /// The sub-table `key`; empty when it's missing
+    pub fn section(&self, key: &str, errors: &mut Vec<String>) -> Section<'t> {
+        let table = self.item(key).and_then(|item| self.typed(key, item.as_table_like(), "a table", errors));
+        Section { name: self.key_path(key), table }
+    }
+
+    /// Each table of a `[[key]]` array
+    pub fn sections(&self, key: &str, errors: &mut Vec<String>) -> Vec<Section<'t>> {
+        let Some(item) = self.item(key) else {
+            return Vec::new();
+        };
+        let Some(tables) = self.typed(key, item.as_array_of_tables(), &format!("[[{}]] tables", key), errors) else {
+            return Vec::new();
+        };
+        tables
+            .iter()
+            .enumerate()
+            .map(|(index, table)| Section { name: format!("{}[{}]", self.key_path(key), index), table: Some(table as &dyn TableLike) })
+            .collect()
+    }
+}
+
+/// Set `key` only if its value changed, keeping any comment written beside it
+pub fn set(table: &mut Table, key: &str, new: impl Into<Value>) {
+    let mut new = new.into();
+    match table.get_mut(key) {
+        Some(Item::Value(old)) => {
+            *new.decor_mut() = old.decor().clone();
+            if old.to_string() != new.to_string() {
+                *old = new;
             }
         }
+        Some(item) => *item = Item::Value(new),
+        None => {
+            table.insert(key, Item::Value(new));
+        }
     }
-    
-    // If no workflow folders were loaded, create defaults
-    if workflow_folders.is_empty() {
-        for destination in DEFAULT_MOVE_DESTINATIONS {
-            workflow_folders.push(root.join(destination));
+}
+
+pub fn set_or_remove(table: &mut Table, key: &str, new: Option<impl Into<Value>>) {
+    match new {
+        Some(new) => set(table, key, new),
+        None => {
+            table.remove(key);
+        }
+    }
+}
+
+pub fn string_array<'s>(values: impl IntoIterator<Item = &'s str>) -> Array {
+    values.into_iter().collect()
+}
+
+/// The sub-table `key`, created if it's missing
+pub fn table_mut<'t>(table: &'t mut Table, key: &str) -> &'t mut Table {
+    let item = table.entry(key).or_insert_with(toml_edit::table);
+    if let Some(inline) = item.as_inline_table_mut() {
+        *item = Item::Table(std::mem::take(inline).into_table());
+    } else if !item.is_table() {
+        *item = toml_edit::table();
+    }
+    let table = item.as_table_mut().expect("replaced with a table above");
+    // A table holding only other tables (or nothing yet) doesn't need its own header
+    if table.is_empty() {
+        table.set_implicit(true);
+    }
+    table
+}
+
+impl Config {
+    fn from_document(doc: &DocumentMut, mut errors: Vec<String>) -> Self {
+        let config = Section::new(doc.as_table() as &dyn TableLike);
+        config.check_keys(
+            &["version", "root", "template_root", "daily_template", "theme", "history_frontmatter", "ignore", "editor", "file_list", "bookmarks", "vaults", "trusted_vaults", "stages", "keys"],
+            &mut errors,
+        );
+        if let Some(version) = config.item("version") {
+            match version.as_integer() {
+                Some(version) if version > CONFIG_VERSION => errors.push(format!(
+                    "version: {} was written by a newer release (this one reads {}), so some settings may be ignored",
+                    version, CONFIG_VERSION
+                )),
+                Some(version) if version >= 1 => {}
+                _ => errors.push(format!("version: expected a number from 1 to {}", CONFIG_VERSION)),
+            }
+        }
+
+        let root = config.path("root", &mut errors).unwrap_or_else(|| PathBuf::from("."));
+        let daily_template = config.path("daily_template", &mut errors);
+        let history_frontmatter = config.bool("history_frontmatter", &mut errors).unwrap_or(false);
+        // Relative paths in the global config are relative to the working directory
+        let settings = LayerSettings::from_config(&config, Path::new(""), &mut errors);
+        let editor_options = EditorOptions::from_config(&config.section("editor", &mut errors), &mut errors);
+        let file_list_options = FileListOptions::from_config(&config.section("file_list", &mut errors), &mut errors);
+        let bookmarks = Bookmarks::from_config(&config.section("bookmarks", &mut errors), &mut errors);
+        let vaults = read_vaults(&config, &mut errors);
+        let trusted_vaults = read_trusted_vaults(&config.section("trusted_vaults", &mut errors), &mut errors);
+        let keymap = Keymap::from_config(&config.section("keys", &mut errors), &mut errors);
+
+        Self {
+            root,
+            daily_template,
+            history_frontmatter,
+            settings,
+            editor_options,
+            file_list_options,
+            bookmarks,
+            vaults,
+            trusted_vaults,
+            keymap,
+            errors,
         }
     }
+}
+
+/// Read config.toml, importing the legacy file the first time round
+pub fn load_config() -> Config {
+    let mut errors = Vec::new();
+    let doc = load_document(&mut errors);
+    Config::from_document(&doc, errors)
+}
+
+fn load_document(errors: &mut Vec<String>) -> DocumentMut {
+    let Some(path) = config_path() else {
+        return DocumentMut::new();
+    };
+    if let Ok(content) = fs::read_to_string(&path) {
+        return content.parse().unwrap_or_else(|error| {
+            errors.push(format!("{}: {}", path.display(), error));
+            DocumentMut::new()
+        });
+    }
 
-    (root, template_root, color_scheme, workflow_folders)
+    let Some(content) = legacy_config_path().and_then(|legacy| fs::read_to_string(legacy).ok()) else {
+        return DocumentMut::new();
+    };
+    let doc = migrate_legacy(&content, errors);
+    if let Err(error) = write_document(&path, &doc) {
+        errors.push(format!("{}: couldn't save imported settings: {}", path.display(), error));
+    }
+    doc
 }
 
-pub fn save_config(root: &PathBuf, template_root: &Option<PathBuf>, color_scheme: &ColorScheme, workflow_folders: &Vec<PathBuf>) {
-    if let Some(path) = config_path() {
-        let mut content = format!("root={}\n", root.to_string_lossy());
-        if let Some(tmpl_root) = template_root {
-            content.push_str(&format!("template_root={}\n", tmpl_root.to_string_lossy()));
+fn write_document(path: &Path, doc: &DocumentMut) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, doc.to_string())
+}
+
+/// `prefix_N` values in index order
+fn numbered<'c>(legacy: &BTreeMap<&str, &'c str>, prefix: &str) -> Vec<&'c str> {
+    let mut numbered: Vec<(usize, &str)> = legacy
+        .iter()
+        .filter_map(|(key, value)| Some((key.strip_prefix(prefix)?.parse().ok()?, value.trim())))
+        .collect();
+    numbered.sort_by_key(|(index, _)| *index);
+    numbered.into_iter().map(|(_, value)| value).collect()
+}
+
+/// Version 0 to 1: turn the old `key=value` lines into the current layout
+fn migrate_legacy(content: &str, errors: &mut Vec<String>) -> DocumentMut {
+    let legacy: BTreeMap<&str, &str> = content.lines().filter_map(|line| line.split_once('=')).collect();
+    let text = |key: &str| legacy.get(key).map(|value| value.trim());
+    let mut doc = DocumentMut::new();
+    let config = doc.as_table_mut();
+    set(config, "version", CONFIG_VERSION);
+    for (old, new) in [("root", "root"), ("template_root", "template_root"), ("daily_template", "daily_template"), ("color_scheme", "theme")] {
+        if let Some(value) = text(old) {
+            set(config, new, value);
+        }
+    }
+    if text("history_frontmatter") == Some("true") {
+        set(config, "history_frontmatter", true);
+    }
+
+    let editor = table_mut(config, "editor");
+    for key in ["line_numbers", "soft_wrap"] {
+        if let Some(value) = text(key) {
+            set(editor, key, value);
         }
-        content.push_str(&format!("color_scheme={}\n", color_scheme.to_string()));
-        
-        // Save workflow folders
-        for (i, folder) in workflow_folders.iter().enumerate() {
-            content.push_str(&format!("workflow_{}={}\n", i, folder.to_string_lossy()));
+    }
+    if let Some(ruler) = text("ruler") {
+        set(editor, "ruler", ruler == "true");
+    }
+    if let Some(width) = text("text_width").and_then(|width| width.parse::<i64>().ok()) {
+        set(editor, "text_width", width);
+    }
+
+    let file_list = table_mut(config, "file_list");
+    if let Some(details) = text("file_details") {
+        set(file_list, "details", details == "true");
+    }
+    let sort = table_mut(file_list, "sort");
+    for (key, value) in &legacy {
+        if let Some(dir) = key.strip_prefix("sort:") {
+            set(sort, dir, value.trim());
         }
-        
-        fs::write(path, content).ok();
     }
-}
\ No newline at end of file
+
+    let bookmarks = table_mut(config, "bookmarks");
+    set(bookmarks, "pinned", string_array(numbered(&legacy, "pin_")));
+    set(bookmarks, "recent", string_array(numbered(&legacy, "recent_")));
+
+    let mut stages = Vec::new();
+    for index in 0.. {
+        let Some(name) = text(&format!("stage_{}", index)) else {
+            break;
+        };
+        let mut stage = Table::new();
+        set(&mut stage, "name", name);
+        for key in ["folder", "color", "icon", "pre_hook", "post_hook"] {
+            if let Some(value) = text(&format!("stage_{}_{}", index, key)).filter(|value| !value.is_empty()) {
+                set(&mut stage, key, value);
+            }
+        }
+        // An empty list in the old file allowed any next stage, which v1 writes by leaving `next` out
+        if let Some(next) = text(&format!("stage_{}_next", index)).filter(|next| !next.trim().is_empty()) {
+            set(&mut stage, "next", string_array(next.split(',').map(str::trim).filter(|name| !name.is_empty())));
+        }
+        if let Some(wip) = text(&format!("stage_{}_wip", index)).and_then(|wip| wip.parse::<i64>().ok()) {
+            set(&mut stage, "wip", wip);
+        }
+        stages.push(stage);
+    }
+    // Older files only moved the default stages' folders with `workflow_N`
+    if stages.is_empty() && (0..DEFAULT_MOVE_DESTINATIONS.len()).any(|index| text(&format!("workflow_{}", index)).is_some()) {
+        let root = PathBuf::from(text("root").unwrap_or("."));
+        for (index, destination) in DEFAULT_MOVE_DESTINATIONS.iter().enumerate() {
+            let folder = text(&format!("workflow_{}", index))
+                .map(PathBuf::from)
+                .unwrap_or_else(|| root.join(destination));
+            let mut stage = Table::new();
+            set(&mut stage, "name", *destination);
+            set(&mut stage, "folder", folder.to_string_lossy().as_ref());
+            stages.push(stage);
+        }
+    }
+    if !stages.is_empty() {
+        config.insert("stages", Item::ArrayOfTables(stages.into_iter().collect()));
+    }
+
+    for (key, value) in legacy.iter().filter(|(key, _)| key.starts_with("key.")) {
+        let mut parts = key.trim().splitn(3, '.').skip(1);
+        let (Some(mode), Some(action)) = (parts.next(), parts.next()) else {
+            errors.push(format!("{}: couldn't carry over from the old config, expected key.<mode>.<action>", key));
+            continue;
+        };
+        let keys = table_mut(config, "keys");
+        let mode = table_mut(keys, mode);
+        set(mode, action, string_array(value.split(',').map(str::trim).filter(|keys| !keys.is_empty())));
+    }
+    doc
+}
+
+impl<'a> App<'a> {
+    /// Write the current settings into config.toml (and the vault's for settings it overrides), leaving comments, key overrides and anything unrecognised alone
+    pub fn save_config(&mut self) {
+        let Some(path) = config_path() else {
+            return;
+        };
+        let mut doc = match fs::read_to_string(&path) {
+            // A file that doesn't parse is left for the user to fix rather than overwritten
+            Ok(content) => match content.parse::<DocumentMut>() {
+                Ok(doc) => doc,
+                Err(_) => return,
+            },
+            Err(_) => DocumentMut::new(),
+        };
+        let config = doc.as_table_mut();
+        set(config, "version", CONFIG_VERSION);
+        set(config, "root", self.root.to_string_lossy().as_ref());
+        set_or_remove(config, "daily_template", self.daily_template.as_ref().map(|path| path.to_string_lossy().to_string()));
+        set(config, "history_frontmatter", self.history_frontmatter);
+        self.write_layered_settings(config);
+        self.editor_options.write_config(table_mut(config, "editor"));
+        self.file_list_options.write_config(table_mut(config, "file_list"));
+        self.bookmarks.write_config(table_mut(config, "bookmarks"));
+        write_vault_sessions(&self.vaults, config);
+        write_trusted_vaults(&self.trusted_vaults, table_mut(config, "trusted_vaults"));
+        write_document(&path, &doc).ok();
+    }
+
+    pub fn close_config_errors(&mut self) {
+        self.mode = Mode::Normal;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::Action;
+    use crate::editor_options::{LineNumbers, SoftWrap};
+    use crate::file_sort::{FileSort, SortKey};
+    use crate::ui::themes::ColorScheme;
+
+    /// Migrate `legacy`, then read the written text back the way the next launch would
+    fn migrate(legacy: &str) -> (String, Config) {
+        let mut errors = Vec::new();
+        let text = migrate_legacy(legacy, &mut errors).to_string();
+        assert!(errors.is_empty(), "migration errors: {:?}", errors);
+        let doc = text.parse::<DocumentMut>().expect("migrated config parses");
+        let config = Config::from_document(&doc, Vec::new());
+        assert!(config.errors.is_empty(), "errors reading migrated config: {:?}\n{}", config.errors, text);
+        (text, config)
+    }
+
+    #[test]
+    fn migrates_top_level_editor_and_file_list_settings() {
+        let template_root = std::env::temp_dir();
+        let legacy = format!(
+            "root=/notes\ntemplate_root={}\ndaily_template=/notes/daily.md\ncolor_scheme=Amber\nhistory_frontmatter=true\n\
+             line_numbers=relative\nsoft_wrap=column\nruler=true\ntext_width=72\nfile_details=true\nsort:/notes/a=modified desc\n",
+            template_root.display()
+        );
+        let (text, config) = migrate(&legacy);
+        assert!(text.starts_with("version = 1\n"));
+        assert_eq!(config.root, PathBuf::from("/notes"));
+        assert_eq!(config.daily_template, Some(PathBuf::from("/notes/daily.md")));
+        assert!(config.history_frontmatter);
+        assert_eq!(config.settings.template_root, Some(template_root));
+        assert_eq!(config.settings.color_scheme, Some(ColorScheme::Amber));
+        assert!(config.editor_options.line_numbers == LineNumbers::Relative);
+        assert!(config.editor_options.soft_wrap == SoftWrap::Column);
+        assert!(config.editor_options.show_ruler);
+        assert_eq!(config.editor_options.text_width, 72);
+        assert!(config.file_list_options.show_details);
+        assert!(config.file_list_options.sorts.get(&PathBuf::from("/notes/a")) == Some(&FileSort { key: SortKey::Modified, descending: true }));
+    }
+
+    #[test]
+    fn migrates_bookmarks_in_index_order() {
+        let (_, config) = migrate("pin_10=/c.md\npin_0=/a.md\npin_2=/b.md\nrecent_1=/y.md\nrecent_0=/x.md\n");
+        assert_eq!(config.bookmarks.pinned, ["/a.md", "/b.md", "/c.md"].map(PathBuf::from));
+        assert_eq!(config.bookmarks.recent, ["/x.md", "/y.md"].map(PathBuf::from));
+    }
+
+    #[test]
+    fn migrates_stages() {
+        let (_, config) = migrate(
+            "stage_0=Draft\nstage_0_folder=/notes/draft\nstage_0_color=Yellow\nstage_0_icon=✎\nstage_0_next=Review, Done\n\
+             stage_0_wip=3\nstage_0_pre_hook=echo pre\nstage_1=Review\nstage_1_next=\nstage_1_post_hook=echo post\n",
+        );
+        let stages = config.settings.stages.expect("stages were carried over");
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].name, "Draft");
+        assert_eq!(stages[0].folder, PathBuf::from("/notes/draft"));
+        assert_eq!(stages[0].color, ratatui::style::Color::Yellow);
+        assert_eq!(stages[0].icon, "✎");
+        assert_eq!(stages[0].transitions, Some(vec!["Review".to_string(), "Done".to_string()]));
+        assert_eq!(stages[0].wip_limit, Some(3));
+        assert_eq!(stages[0].pre_hook.as_deref(), Some("echo pre"));
+        // An empty `next` used to allow any stage
+        assert_eq!(stages[1].transitions, None);
+        assert_eq!(stages[1].post_hook.as_deref(), Some("echo post"));
+    }
+
+    #[test]
+    fn migrates_workflow_folders_onto_the_default_stages() {
+        let (_, config) = migrate("root=/notes\nworkflow_1=/elsewhere/rendered\n");
+        let stages = config.settings.stages.expect("default stages were written");
+        let names: Vec<&str> = stages.iter().map(|stage| stage.name.as_str()).collect();
+        assert_eq!(names, DEFAULT_MOVE_DESTINATIONS);
+        assert_eq!(stages[0].folder, PathBuf::from("/notes").join(DEFAULT_MOVE_DESTINATIONS[0]));
+        assert_eq!(stages[1].folder, PathBuf::from("/elsewhere/rendered"));
+    }
+
+    #[test]
+    fn migrates_key_overrides() {
+        let (text, config) = migrate("key.normal.quit=Ctrl+Q, g q\nkey.normal.open_board=\n");
+        assert!(text.contains("[keys.normal]"));
+        assert_eq!(config.keymap.keys_for(Mode::Normal, Action::Quit, false), ["Ctrl+Q", "g q"]);
+        assert!(config.keymap.keys_for(Mode::Normal, Action::OpenBoard, false).is_empty());
+    }
+
+    #[test]
+    fn reports_key_overrides_it_cannot_place() {
+        let mut errors = Vec::new();
+        migrate_legacy("key.normal=q\n", &mut errors);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("key.normal:"));
+    }
+
+    #[test]
+    fn migrates_an_empty_file_to_defaults() {
+        let (text, config) = migrate("");
+        assert!(!text.contains("[keys]"));
+        assert!(!text.contains("[file_list.sort]"));
+        assert_eq!(config.root, PathBuf::from("."));
+        assert!(config.settings.stages.is_none());
+        assert!(config.bookmarks.pinned.is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use toml_edit::Table;
+
+use crate::app::App;
+use crate::config::{set, Section};
+
+pub const DEFAULT_TEXT_WIDTH: usize = 80;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum LineNumbers {
+    Off,
+    Absolute,
+    Relative,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum SoftWrap {
+    Off,
+    Pane,
+    Column,
+}
+
+/// View settings for the editor, persisted alongside the rest of the config
+pub struct EditorOptions {
+    pub line_numbers: LineNumbers,
+    pub soft_wrap: SoftWrap,
+    pub show_ruler: bool,
+    pub text_width: usize,
+}
+
+impl LineNumbers {
+    pub fn next(self) -> Self {
+        match self {
+            LineNumbers::Off => LineNumbers::Absolute,
+            LineNumbers::Absolute => LineNumbers::Relative,
+            LineNumbers::Relative => LineNumbers::Off,
+        }
+    }
+
+    pub fn from_string(s: &str) -> Self {
+        match s {
+            "absolute" => LineNumbers::Absolute,
+            "relative" => LineNumbers::Relative,
+            _ => LineNumbers::Off,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineNumbers::Off => "off",
+            LineNumbers::Absolute => "absolute",
+            LineNumbers::Relative => "relative",
+        }
+    }
+}
+
+impl SoftWrap {
+    pub fn next(self) -> Self {
+        match self {
+            SoftWrap::Off => SoftWrap::Pane,
+            SoftWrap::Pane => SoftWrap::Column,
+            SoftWrap::Column => SoftWrap::Off,
+        }
+    }
+
+    pub fn from_string(s: &str) -> Self {
+        match s {
+            "pane" => SoftWrap::Pane,
+            "column" => SoftWrap::Column,
+            _ => SoftWrap::Off,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SoftWrap::Off => "off",
+            SoftWrap::Pane => "pane",
+            SoftWrap::Column => "column",
+        }
+    }
+}
+
+impl EditorOptions {
+    pub fn from_config(config: &Section, errors: &mut Vec<String>) -> Self {
+        config.check_keys(&["line_numbers", "soft_wrap", "ruler", "text_width"], errors);
+        Self {
+            line_numbers: config
+                .choice("line_numbers", &["off", "absolute", "relative"], errors)
+                .map(LineNumbers::from_string)
+                .unwrap_or(LineNumbers::Off),
+            soft_wrap: config
+                .choice("soft_wrap", &["off", "pane", "column"], errors)
+                .map(SoftWrap::from_string)
+                .unwrap_or(SoftWrap::Off),
+            show_ruler: config.bool("ruler", errors).unwrap_or(false),
+            text_width: config.positive("text_width", errors).unwrap_or(DEFAULT_TEXT_WIDTH),
+        }
+    }
+
+    pub fn write_config(&self, config: &mut Table) {
+        set(config, "line_numbers", self.line_numbers.as_str());
+        set(config, "soft_wrap", self.soft_wrap.as_str());
+        set(config, "ruler", self.show_ruler);
+        set(config, "text_width", self.text_width as i64);
+    }
+
+    /// Column to wrap at for a pane `available` cells wide, or `None` when soft wrap is off
+    pub fn wrap_width(&self, available: usize) -> Option<usize> {
+        match self.soft_wrap {
+            SoftWrap::Off => None,
+            SoftWrap::Pane => Some(available.max(1)),
+            SoftWrap::Column => Some(self.text_width.min(available).max(1)),
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn cycle_line_numbers(&mut self) {
+        self.editor_options.line_numbers = self.editor_options.line_numbers.next();
+        self.save_config();
+    }
+
+    pub fn cycle_soft_wrap(&mut self) {
+        self.editor_options.soft_wrap = self.editor_options.soft_wrap.next();
+        self.save_config();
+    }
+
+    pub fn toggle_ruler(&mut self) {
+        self.editor_options.show_ruler = !self.editor_options.show_ruler;
+        self.save_config();
+    }
+}
@@ -1,7 +1,32 @@
-use std::{fs, path::PathBuf};
+use regex::Regex;
+use std::{fs, path::{Path, PathBuf}};
 use walkdir::WalkDir;
 
-pub fn load_files(root: &PathBuf) -> Vec<PathBuf> {
+/// Paths left out of the file list, task scan and board: `*` globs on names, or trailing paths like `drafts/old`
+#[derive(Default)]
+pub struct IgnoreList {
+    pub patterns: Vec<String>,
+    names: Vec<Regex>,
+}
+
+impl IgnoreList {
+    pub fn new(patterns: Vec<String>) -> Self {
+        let names = patterns
+            .iter()
+            .filter(|pattern| !pattern.contains('/'))
+            .filter_map(|pattern| Regex::new(&format!("^{}$", regex::escape(pattern).replace(r"\*", ".*"))).ok())
+            .collect();
+        Self { patterns, names }
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        self.names.iter().any(|pattern| pattern.is_match(&name))
+            || self.patterns.iter().any(|pattern| pattern.contains('/') && path.ends_with(pattern.trim_matches('/')))
+    }
+}
+
+pub fn load_files(root: &PathBuf, ignore: &IgnoreList) -> Vec<PathBuf> {
     let walkdir = WalkDir::new(root).max_depth(1);
     let mut entries: Vec<PathBuf> = walkdir
         .into_iter()
@@ -9,7 +34,8 @@ pub fn load_files(root: &PathBuf) -> Vec<PathBuf> {
         .map(|e| e.path().to_path_buf())
         .filter(|p| {
             *p != *root &&
-            !p.file_name().unwrap_or_default().to_string_lossy().starts_with(".")
+            !p.file_name().unwrap_or_default().to_string_lossy().starts_with(".") &&
+            !ignore.matches(p)
         })
         .collect();
 
@@ -46,11 +72,14 @@ pub fn load_browser_entries(current_path: &PathBuf) -> Vec<PathBuf> {
 }
 
 pub fn load_template_files(template_root: &PathBuf) -> Vec<PathBuf> {
-    WalkDir::new(template_root)
-        .max_depth(1)
+    let mut templates: Vec<PathBuf> = WalkDir::new(template_root)
         .into_iter()
+        .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'))
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
         .map(|e| e.into_path())
-        .collect()
+        .collect();
+    // Top-level templates first, then each subfolder's
+    templates.sort_by_key(|path| (path.parent().map(PathBuf::from), path.clone()));
+    templates
 }
\ No newline at end of file
@@ -0,0 +1,226 @@
+use std::{cmp::Ordering, collections::BTreeMap, fs, path::{Path, PathBuf}, time::SystemTime};
+
+use toml_edit::Table;
+
+use crate::app::App;
+use crate::config::{set, table_mut, Section};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum SortKey {
+    Name,
+    Modified,
+    Created,
+    Size,
+    WordCount,
+    Title,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub struct FileSort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+/// How the file list is ordered and shown, persisted alongside the rest of the config
+pub struct FileListOptions {
+    pub sorts: BTreeMap<PathBuf, FileSort>,
+    pub show_details: bool,
+}
+
+/// The value a file is compared on for a given `SortKey`
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortValue {
+    Text(String),
+    Number(u64),
+    Time(SystemTime),
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Name => SortKey::Modified,
+            SortKey::Modified => SortKey::Created,
+            SortKey::Created => SortKey::Size,
+            SortKey::Size => SortKey::WordCount,
+            SortKey::WordCount => SortKey::Title,
+            SortKey::Title => SortKey::Name,
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s {
+            "name" => Some(SortKey::Name),
+            "modified" => Some(SortKey::Modified),
+            "created" => Some(SortKey::Created),
+            "size" => Some(SortKey::Size),
+            "words" => Some(SortKey::WordCount),
+            "title" => Some(SortKey::Title),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SortKey::Name => "name",
+            SortKey::Modified => "modified",
+            SortKey::Created => "created",
+            SortKey::Size => "size",
+            SortKey::WordCount => "words",
+            SortKey::Title => "title",
+        }
+    }
+}
+
+impl Default for FileSort {
+    fn default() -> Self {
+        Self { key: SortKey::Name, descending: false }
+    }
+}
+
+impl FileSort {
+    /// `"modified desc"`; the order defaults to ascending
+    pub fn from_string(s: &str) -> Option<Self> {
+        let mut parts = s.split_whitespace();
+        let key = SortKey::from_string(parts.next()?)?;
+        let descending = match parts.next() {
+            None | Some("asc") => false,
+            Some("desc") => true,
+            Some(_) => return None,
+        };
+        parts.next().is_none().then_some(Self { key, descending })
+    }
+
+    pub fn label(self) -> String {
+        format!("{} {}", self.key.as_str().to_uppercase(), if self.descending { "↓" } else { "↑" })
+    }
+}
+
+impl FileListOptions {
+    pub fn from_config(config: &Section, errors: &mut Vec<String>) -> Self {
+        config.check_keys(&["details", "sort"], errors);
+        let sort = config.section("sort", errors);
+        let mut sorts = BTreeMap::new();
+        for dir in sort.keys() {
+            let Some(value) = sort.str(dir, errors) else {
+                continue;
+            };
+            match FileSort::from_string(value.trim()) {
+                Some(file_sort) => {
+                    sorts.insert(PathBuf::from(dir), file_sort);
+                }
+                None => errors.push(format!("{}: expected \"<name|modified|created|size|words|title> <asc|desc>\"", sort.key_path(dir))),
+            }
+        }
+        Self {
+            sorts,
+            show_details: config.bool("details", errors).unwrap_or(false),
+        }
+    }
+
+    pub fn write_config(&self, config: &mut Table) {
+        set(config, "details", self.show_details);
+        let sort = table_mut(config, "sort");
+        let saved: Vec<String> = sort.iter().map(|(dir, _)| dir.to_string()).collect();
+        for dir in saved {
+            if !self.sorts.get(Path::new(&dir)).is_some_and(|file_sort| *file_sort != FileSort::default()) {
+                sort.remove(&dir);
+            }
+        }
+        for (dir, file_sort) in &self.sorts {
+            if *file_sort != FileSort::default() {
+                let order = if file_sort.descending { "desc" } else { "asc" };
+                set(sort, &dir.to_string_lossy(), format!("{} {}", file_sort.key.as_str(), order));
+            }
+        }
+    }
+}
+
+/// The `title:` value in a note's frontmatter, falling back to the filename
+fn note_title(path: &Path) -> String {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split("\n---").next())
+        .and_then(|frontmatter| frontmatter.lines().find_map(|line| line.strip_prefix("title:")))
+        .map(|title| title.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| path.file_stem().unwrap_or_default().to_string_lossy().to_string())
+        .to_lowercase()
+}
+
+fn sort_value(path: &Path, key: SortKey) -> SortValue {
+    let metadata = fs::metadata(path).ok();
+    match key {
+        SortKey::Name => SortValue::Text(path.file_name().unwrap_or_default().to_string_lossy().to_lowercase()),
+        SortKey::Modified => SortValue::Time(metadata.and_then(|meta| meta.modified().ok()).unwrap_or(SystemTime::UNIX_EPOCH)),
+        SortKey::Created => SortValue::Time(metadata.and_then(|meta| meta.created().ok()).unwrap_or(SystemTime::UNIX_EPOCH)),
+        SortKey::Size => SortValue::Number(metadata.map(|meta| meta.len()).unwrap_or(0)),
+        SortKey::WordCount => SortValue::Number(word_count(path) as u64),
+        SortKey::Title => SortValue::Text(note_title(path)),
+    }
+}
+
+fn word_count(path: &Path) -> usize {
+    fs::read_to_string(path).map(|content| content.split_whitespace().count()).unwrap_or(0)
+}
+
+/// Sort folders first, then files by `sort`, reading each file at most once
+pub fn sort_entries(entries: &mut Vec<PathBuf>, sort: FileSort) {
+    let mut keyed: Vec<(bool, SortValue, PathBuf)> = entries
+        .drain(..)
+        .map(|path| (path.is_dir(), sort_value(&path, sort.key), path))
+        .collect();
+    keyed.sort_by(|a, b| {
+        let by_value = a.1.cmp(&b.1).then_with(|| a.2.cmp(&b.2));
+        let by_value = if sort.descending { by_value.reverse() } else { by_value };
+        match b.0.cmp(&a.0) {
+            Ordering::Equal => by_value,
+            folders_first => folders_first,
+        }
+    });
+    entries.extend(keyed.into_iter().map(|(_, _, path)| path));
+}
+
+/// Human readable file size for the detail view
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} K", bytes as f64 / 1024.0),
+        _ => format!("{:.1} M", bytes as f64 / 1_048_576.0),
+    }
+}
+
+impl<'a> App<'a> {
+    fn sort_dir(&self) -> PathBuf {
+        fs::canonicalize(&self.root).unwrap_or_else(|_| self.root.clone())
+    }
+
+    pub fn current_sort(&self) -> FileSort {
+        self.file_list_options.sorts.get(&self.sort_dir()).copied().unwrap_or_default()
+    }
+
+    fn set_sort(&mut self, sort: FileSort) {
+        let selected = self.selected_file().cloned();
+        let dir = self.sort_dir();
+        self.file_list_options.sorts.insert(dir, sort);
+        self.save_config();
+        self.load_files();
+        let index = selected.and_then(|path| self.files.iter().position(|file| *file == path));
+        self.file_list_state.select(index.or(if self.files.is_empty() { None } else { Some(0) }));
+    }
+
+    pub fn cycle_sort_key(&mut self) {
+        let sort = self.current_sort();
+        self.set_sort(FileSort { key: sort.key.next(), ..sort });
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        let sort = self.current_sort();
+        self.set_sort(FileSort { descending: !sort.descending, ..sort });
+    }
+
+    pub fn toggle_file_details(&mut self) {
+        self.file_list_options.show_details = !self.file_list_options.show_details;
+        self.save_config();
+    }
+}
@@ -0,0 +1,330 @@
+use regex::{Captures, Regex, RegexBuilder};
+use tui_textarea::{CursorMove, TextArea};
+
+use crate::app::App;
+use crate::modes::Mode;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum FindField {
+    Query,
+    Replacement,
+}
+
+pub struct FindState {
+    pub query: String,
+    pub replacement: String,
+    pub field: FindField,
+    pub use_regex: bool,
+    pub case_sensitive: bool,
+    pub match_count: usize,
+    pub error: Option<String>,
+}
+
+/// A replace applied through the editor's own history, so it can be undone as one step
+pub struct ReplaceSnapshot {
+    /// The text right after the replace; undo only groups the steps while the editor is back at this state
+    pub lines: Vec<String>,
+    pub steps: usize,
+}
+
+/// (row, col) of the `offset`th character when `lines` are joined by newlines
+fn position_at(lines: &[String], mut offset: usize) -> (usize, usize) {
+    for (row, line) in lines.iter().enumerate() {
+        let len = line.chars().count();
+        if offset <= len {
+            return (row, offset);
+        }
+        offset -= len + 1;
+    }
+    (lines.len().saturating_sub(1), 0)
+}
+
+/// Edit `editor` until it holds `lines`, replacing only the span that differs; returns how many history steps that took
+pub fn edit_to_match(editor: &mut TextArea, lines: &[String]) -> usize {
+    let old: Vec<char> = editor.lines().join("\n").chars().collect();
+    let new: Vec<char> = lines.join("\n").chars().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old.iter().rev().zip(new.iter().rev()).take(max_suffix).take_while(|(a, b)| a == b).count();
+    let removed = old.len() - prefix - suffix;
+    let inserted: String = new[prefix..new.len() - suffix].iter().collect();
+
+    let (start_row, start_col) = position_at(editor.lines(), prefix);
+    let (end_row, end_col) = position_at(editor.lines(), prefix + removed);
+    editor.cancel_selection();
+    editor.move_cursor(CursorMove::Jump(start_row as u16, start_col as u16));
+    let mut steps = 0;
+    if removed > 0 {
+        editor.start_selection();
+        editor.move_cursor(CursorMove::Jump(end_row as u16, end_col as u16));
+        editor.delete_str(0);
+        steps += 1;
+    }
+    if !inserted.is_empty() {
+        editor.insert_str(&inserted);
+        steps += 1;
+    }
+    steps
+}
+
+impl FindState {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            replacement: String::new(),
+            field: FindField::Query,
+            use_regex: false,
+            case_sensitive: false,
+            match_count: 0,
+            error: None,
+        }
+    }
+
+    /// Pattern string in the form `TextArea::set_search_pattern` expects
+    pub fn pattern(&self) -> String {
+        let body = if self.use_regex {
+            self.query.clone()
+        } else {
+            regex::escape(&self.query)
+        };
+        if self.case_sensitive || body.is_empty() {
+            body
+        } else {
+            format!("(?i){}", body)
+        }
+    }
+
+    pub fn compile(&self) -> Result<Regex, regex::Error> {
+        let body = if self.use_regex {
+            self.query.clone()
+        } else {
+            regex::escape(&self.query)
+        };
+        RegexBuilder::new(&body)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+    }
+
+    /// Replace every match in a single line, returning the new line and the number of replacements
+    fn replace_line(&self, regex: &Regex, line: &str) -> (String, usize) {
+        let count = regex.find_iter(line).filter(|m| !m.is_empty()).count();
+        if count == 0 {
+            return (line.to_string(), 0);
+        }
+        // Empty matches aren't counted, so they're left as they are rather than replaced
+        let replaced = regex.replace_all(line, |captures: &Captures| {
+            let mut text = String::new();
+            if captures[0].is_empty() {
+                return text;
+            }
+            if self.use_regex {
+                captures.expand(&self.replacement, &mut text);
+            } else {
+                text.push_str(&self.replacement);
+            }
+            text
+        });
+        (replaced.into_owned(), count)
+    }
+}
+
+/// Byte offset of a char column within a line
+fn byte_offset(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map(|(i, _)| i).unwrap_or(line.len())
+}
+
+impl<'a> App<'a> {
+    pub fn open_find(&mut self) {
+        if self.buffers.is_empty() {
+            return;
+        }
+        self.find.field = FindField::Query;
+        self.mode = Mode::Finding;
+        self.refresh_find();
+    }
+
+    pub fn close_find(&mut self) {
+        if let Some(editor) = self.active_editor_mut() {
+            editor.set_search_pattern("").ok();
+        }
+        self.find.error = None;
+        self.mode = Mode::Editing;
+    }
+
+    pub fn toggle_find_field(&mut self) {
+        self.find.field = match self.find.field {
+            FindField::Query => FindField::Replacement,
+            FindField::Replacement => FindField::Query,
+        };
+    }
+
+    pub fn find_input_char(&mut self, c: char) {
+        match self.find.field {
+            FindField::Query => {
+                self.find.query.push(c);
+                self.refresh_find();
+            }
+            FindField::Replacement => self.find.replacement.push(c),
+        }
+    }
+
+    pub fn find_backspace(&mut self) {
+        match self.find.field {
+            FindField::Query => {
+                self.find.query.pop();
+                self.refresh_find();
+            }
+            FindField::Replacement => {
+                self.find.replacement.pop();
+            }
+        }
+    }
+
+    pub fn toggle_find_regex(&mut self) {
+        self.find.use_regex = !self.find.use_regex;
+        self.refresh_find();
+    }
+
+    pub fn toggle_find_case(&mut self) {
+        self.find.case_sensitive = !self.find.case_sensitive;
+        self.refresh_find();
+    }
+
+    /// Re-apply the pattern to the editor, jumping to the nearest match as the query is typed
+    pub fn refresh_find(&mut self) {
+        let pattern = self.find.pattern();
+        let Some(editor) = self.active_editor_mut() else {
+            return;
+        };
+
+        let result = editor.set_search_pattern(&pattern);
+        if result.is_ok() && !pattern.is_empty() {
+            editor.search_forward(true);
+        }
+        self.find.error = result.err().map(|err| err.to_string());
+        self.update_match_count();
+    }
+
+    fn update_match_count(&mut self) {
+        self.find.match_count = match (self.active_editor(), self.find.query.is_empty()) {
+            (Some(editor), false) => match self.find.compile() {
+                Ok(regex) => editor
+                    .lines()
+                    .iter()
+                    .map(|line| regex.find_iter(line).filter(|m| !m.is_empty()).count())
+                    .sum(),
+                Err(_) => 0,
+            },
+            _ => 0,
+        };
+    }
+
+    pub fn find_next(&mut self) {
+        if let Some(editor) = self.active_editor_mut() {
+            editor.search_forward(false);
+        }
+    }
+
+    pub fn find_previous(&mut self) {
+        if let Some(editor) = self.active_editor_mut() {
+            editor.search_back(false);
+        }
+    }
+
+    /// Replace the match under the cursor (or the next one) and advance to the following match
+    pub fn replace_current(&mut self) {
+        if self.find.query.is_empty() {
+            return;
+        }
+        let Ok(regex) = self.find.compile() else {
+            return;
+        };
+        let use_regex = self.find.use_regex;
+        let template = self.find.replacement.clone();
+        let Some(editor) = self.active_editor_mut() else {
+            return;
+        };
+        if !editor.search_forward(true) {
+            return;
+        }
+
+        let (row, col) = editor.cursor();
+        let mut lines = editor.lines().to_vec();
+        let line = &lines[row];
+        let start = byte_offset(line, col);
+        let Some(captures) = regex.captures_at(line, start) else {
+            return;
+        };
+        let whole = captures.get(0).expect("capture group 0 always exists");
+        if whole.start() != start || whole.is_empty() {
+            return;
+        }
+
+        let mut replacement = String::new();
+        if use_regex {
+            captures.expand(&template, &mut replacement);
+        } else {
+            replacement.push_str(&template);
+        }
+        let new_line = format!("{}{}{}", &line[..whole.start()], replacement, &line[whole.end()..]);
+        let new_col = col + replacement.chars().count();
+        lines[row] = new_line;
+
+        self.apply_replacement(lines, (row, new_col));
+        if let Some(editor) = self.active_editor_mut() {
+            editor.search_forward(true);
+        }
+    }
+
+    pub fn replace_all(&mut self) {
+        if self.find.query.is_empty() {
+            return;
+        }
+        let Ok(regex) = self.find.compile() else {
+            return;
+        };
+        let Some(editor) = self.active_editor() else {
+            return;
+        };
+
+        let mut total = 0;
+        let lines: Vec<String> = editor
+            .lines()
+            .iter()
+            .map(|line| {
+                let (new_line, count) = self.find.replace_line(&regex, line);
+                total += count;
+                new_line
+            })
+            .collect();
+
+        if total > 0 {
+            let cursor = editor.cursor();
+            self.apply_replacement(lines, cursor);
+        }
+    }
+
+    /// Change the editor contents to `lines` in place, keeping its undo history and grouping the change into one step
+    pub fn apply_replacement(&mut self, lines: Vec<String>, cursor: (usize, usize)) {
+        let Some(buffer) = self.active_buffer_mut() else {
+            return;
+        };
+        buffer.replace_lines(lines, cursor);
+        self.update_match_count();
+    }
+
+    /// Undo in the editor, taking back every step of a replace at once
+    pub fn undo_edit(&mut self) {
+        let Some(buffer) = self.active_buffer_mut() else {
+            return;
+        };
+        let grouped = buffer.replace_history.last().is_some_and(|snapshot| buffer.editor.lines() == snapshot.lines.as_slice());
+        let steps = if grouped { buffer.replace_history.pop().map_or(1, |snapshot| snapshot.steps) } else { 1 };
+        for _ in 0..steps {
+            buffer.editor.undo();
+        }
+        if self.mode == Mode::Finding {
+            self.update_match_count();
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::OnceLock,
+};
+use regex::Regex;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Token {
+    Heading,
+    Emphasis,
+    Strong,
+    Link,
+    InlineCode,
+    ListMarker,
+    BlockQuote,
+    Frontmatter,
+    CodeFence,
+    CodeText,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Shell,
+    Data,
+    Plain,
+}
+
+/// Multi-line context carried from one line to the next
+#[derive(PartialEq, Clone, Debug)]
+pub enum BlockState {
+    Normal,
+    Frontmatter,
+    Fence { marker: char, length: usize, language: Language },
+}
+
+pub struct HighlightedLine {
+    hash: u64,
+    start: BlockState,
+    pub end: BlockState,
+    pub tokens: Vec<(Range<usize>, Token)>,
+}
+
+/// Per-buffer highlight results, recomputed only for lines whose text or incoming state changed
+#[derive(Default)]
+pub struct HighlightCache {
+    lines: Vec<HighlightedLine>,
+}
+
+impl HighlightCache {
+    /// Bring lines `0..bottom` up to date and return them
+    pub fn update(&mut self, lines: &[String], bottom: usize) -> &[HighlightedLine] {
+        let bottom = bottom.min(lines.len());
+        self.lines.truncate(lines.len());
+
+        let mut state = BlockState::Normal;
+        for (index, line) in lines.iter().enumerate().take(bottom) {
+            let hash = hash_line(line);
+            let fresh = self
+                .lines
+                .get(index)
+                .is_some_and(|cached| cached.hash == hash && cached.start == state);
+            if !fresh {
+                let (tokens, end) = highlight_line(line, index, &state);
+                let highlighted = HighlightedLine { hash, start: state.clone(), end, tokens };
+                if index < self.lines.len() {
+                    self.lines[index] = highlighted;
+                } else {
+                    self.lines.push(highlighted);
+                }
+            }
+            state = self.lines[index].end.clone();
+        }
+
+        &self.lines[..bottom]
+    }
+}
+
+fn hash_line(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Language {
+    fn from_info(info: &str) -> Self {
+        match info.split_whitespace().next().unwrap_or_default().to_lowercase().as_str() {
+            "rust" | "rs" => Language::Rust,
+            "python" | "py" => Language::Python,
+            "javascript" | "js" | "typescript" | "ts" | "jsx" | "tsx" => Language::JavaScript,
+            "sh" | "bash" | "zsh" | "shell" | "console" => Language::Shell,
+            "json" | "toml" | "yaml" | "yml" | "ini" => Language::Data,
+            _ => Language::Plain,
+        }
+    }
+
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            Language::Rust => &[
+                "as", "break", "const", "continue", "crate", "else", "enum", "false", "fn", "for", "if", "impl",
+                "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+                "static", "struct", "trait", "true", "type", "use", "where", "while", "async", "await", "dyn",
+            ],
+            Language::Python => &[
+                "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif",
+                "else", "except", "False", "finally", "for", "from", "if", "import", "in", "is", "lambda",
+                "None", "not", "or", "pass", "raise", "return", "True", "try", "while", "with", "yield",
+            ],
+            Language::JavaScript => &[
+                "async", "await", "break", "case", "catch", "class", "const", "continue", "default", "else",
+                "export", "extends", "false", "for", "function", "if", "import", "in", "interface", "let",
+                "new", "null", "of", "return", "switch", "this", "throw", "true", "try", "type", "typeof",
+                "undefined", "var", "while",
+            ],
+            Language::Shell => &[
+                "case", "do", "done", "echo", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+                "in", "local", "return", "then", "while",
+            ],
+            Language::Data => &["true", "false", "null"],
+            Language::Plain => &[],
+        }
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        match self {
+            Language::Rust | Language::JavaScript => Some("//"),
+            Language::Python | Language::Shell | Language::Data => Some("#"),
+            Language::Plain => None,
+        }
+    }
+}
+
+fn inline_patterns() -> &'static [(Regex, Token)] {
+    static PATTERNS: OnceLock<Vec<(Regex, Token)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (r"\*\*[^*]+\*\*|__[^_]+__", Token::Strong),
+            (r"\*[^*\s][^*]*\*|\b_[^_\s][^_]*_\b", Token::Emphasis),
+            (r"!?\[[^\]]*\]\([^)]*\)|<https?://[^>]+>|\[\[[^\]]+\]\]", Token::Link),
+            (r"`[^`]+`", Token::InlineCode),
+        ]
+        .into_iter()
+        .map(|(pattern, token)| (Regex::new(pattern).expect("valid inline pattern"), token))
+        .collect()
+    })
+}
+
+fn list_marker_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)])\s+(?:\[[ xX]\]\s)?").expect("valid list pattern"))
+}
+
+/// Opening or closing fence: up to three spaces, then three or more backticks or tildes
+fn fence_marker(line: &str) -> Option<(char, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let marker = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = rest.chars().take_while(|c| *c == marker).count();
+    if length < 3 {
+        return None;
+    }
+    Some((marker, length, rest[length..].trim()))
+}
+
+/// Highlight one line given the state left by the previous line
+pub fn highlight_line(line: &str, index: usize, state: &BlockState) -> (Vec<(Range<usize>, Token)>, BlockState) {
+    let whole = 0..line.len();
+    match state {
+        BlockState::Frontmatter => {
+            let end = if line == "---" || line == "..." { BlockState::Normal } else { BlockState::Frontmatter };
+            (vec![(whole, Token::Frontmatter)], end)
+        }
+        BlockState::Fence { marker, length, language } => {
+            if let Some((closing, closing_length, rest)) = fence_marker(line) {
+                if closing == *marker && closing_length >= *length && rest.is_empty() {
+                    return (vec![(whole, Token::CodeFence)], BlockState::Normal);
+                }
+            }
+            (highlight_code(line, *language), state.clone())
+        }
+        BlockState::Normal => {
+            if index == 0 && line == "---" {
+                return (vec![(whole, Token::Frontmatter)], BlockState::Frontmatter);
+            }
+            if let Some((marker, length, info)) = fence_marker(line) {
+                let language = Language::from_info(info);
+                return (vec![(whole, Token::CodeFence)], BlockState::Fence { marker, length, language });
+            }
+            (highlight_markdown(line), BlockState::Normal)
+        }
+    }
+}
+
+fn highlight_markdown(line: &str) -> Vec<(Range<usize>, Token)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+
+    if indent <= 3 {
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].chars().next().is_none_or(|c| c == ' ') {
+            return vec![(0..line.len(), Token::Heading)];
+        }
+    }
+
+    let mut tokens = Vec::new();
+    if trimmed.starts_with('>') {
+        tokens.push((0..line.len(), Token::BlockQuote));
+    }
+    if let Some(found) = list_marker_pattern().find(line) {
+        tokens.push((found.range(), Token::ListMarker));
+    }
+    let mut inline: Vec<(Range<usize>, Token)> = Vec::new();
+    for (pattern, token) in inline_patterns() {
+        inline.extend(pattern.find_iter(line).map(|found| (found.range(), *token)));
+    }
+    // Nothing nests inside inline code, and `*x*` inside `**x**` is not separate emphasis
+    let covers = |outer: &Range<usize>, inner: &Range<usize>| outer.start <= inner.start && inner.end <= outer.end;
+    let shadowed = |range: &Range<usize>, token: Token| {
+        inline.iter().any(|(other, other_token)| {
+            other != range
+                && covers(other, range)
+                && (*other_token == Token::InlineCode || (*other_token == Token::Strong && token == Token::Emphasis))
+        })
+    };
+    tokens.extend(inline.iter().filter(|(range, token)| !shadowed(range, *token)).cloned());
+    tokens
+}
+
+fn highlight_code(line: &str, language: Language) -> Vec<(Range<usize>, Token)> {
+    let mut tokens = vec![(0..line.len(), Token::CodeText)];
+    if language == Language::Plain {
+        return tokens;
+    }
+
+    let keywords = language.keywords();
+    let mut chars = line.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if let Some(comment) = language.line_comment() {
+            if line[start..].starts_with(comment) {
+                tokens.push((start..line.len(), Token::Comment));
+                break;
+            }
+        }
+        if c == '"' || c == '\'' || c == '`' {
+            let mut end = line.len();
+            let mut escaped = false;
+            for (i, next) in chars.by_ref() {
+                if next == c && !escaped {
+                    end = i + next.len_utf8();
+                    break;
+                }
+                escaped = next == '\\' && !escaped;
+            }
+            tokens.push((start..end, Token::String));
+        } else if c.is_ascii_digit() {
+            let mut end = start + 1;
+            while let Some((i, next)) = chars.peek() {
+                if next.is_ascii_alphanumeric() || *next == '.' || *next == '_' {
+                    end = i + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push((start..end, Token::Number));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some((i, next)) = chars.peek() {
+                if next.is_alphanumeric() || *next == '_' {
+                    end = i + next.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            if keywords.contains(&&line[start..end]) {
+                tokens.push((start..end, Token::Keyword));
+            }
+        }
+    }
+    tokens
+}
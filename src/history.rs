@@ -0,0 +1,224 @@
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use std::{collections::HashMap, fs, io::Write, path::{Path, PathBuf}};
+use ratatui::widgets::ListState;
+
+use crate::app::App;
+use crate::modes::Mode;
+use crate::workflow::INBOX;
+
+pub const HISTORY_FOLDER: &str = ".nostromo";
+const HISTORY_FILE: &str = "history.log";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// One stage change, as stored in the vault's history log
+pub struct Transition {
+    pub at: NaiveDateTime,
+    pub note: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// A note's transitions, oldest first
+pub struct Journey {
+    pub note: String,
+    pub transitions: Vec<Transition>,
+}
+
+pub struct TimelineState {
+    pub journeys: Vec<Journey>,
+    pub list_state: ListState,
+    pub show_report: bool,
+}
+
+pub fn history_path(root: &Path) -> PathBuf {
+    root.join(HISTORY_FOLDER).join(HISTORY_FILE)
+}
+
+fn append_transition(root: &Path, transition: &Transition) {
+    let path = history_path(root);
+    if let Some(folder) = path.parent() {
+        fs::create_dir_all(folder).ok();
+    }
+    if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(path) {
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}",
+            transition.at.format(TIMESTAMP_FORMAT),
+            transition.note,
+            transition.from,
+            transition.to
+        )
+        .ok();
+    }
+}
+
+pub fn load_transitions(root: &Path) -> Vec<Transition> {
+    let content = fs::read_to_string(history_path(root)).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(Transition {
+                at: NaiveDateTime::parse_from_str(fields.next()?, TIMESTAMP_FORMAT).ok()?,
+                note: fields.next()?.to_string(),
+                from: fields.next()?.to_string(),
+                to: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Group transitions per note, most recently moved first
+pub fn journeys(transitions: Vec<Transition>) -> Vec<Journey> {
+    let mut by_note: HashMap<String, Vec<Transition>> = HashMap::new();
+    for transition in transitions {
+        by_note.entry(transition.note.clone()).or_default().push(transition);
+    }
+    let mut journeys: Vec<Journey> = by_note
+        .into_iter()
+        .map(|(note, mut transitions)| {
+            transitions.sort_by_key(|transition| transition.at);
+            Journey { note, transitions }
+        })
+        .collect();
+    journeys.sort_by_key(|journey| std::cmp::Reverse(journey.transitions.last().map(|transition| transition.at)));
+    journeys
+}
+
+/// Average time notes stayed in each stage before moving on, in the order the stages first turn up in the log
+pub fn average_stage_times(journeys: &[Journey]) -> Vec<(String, TimeDelta, usize)> {
+    let mut totals: Vec<(String, TimeDelta, usize)> = Vec::new();
+    for journey in journeys {
+        for pair in journey.transitions.windows(2) {
+            let stage = &pair[0].to;
+            let spent = pair[1].at - pair[0].at;
+            match totals.iter_mut().find(|(name, _, _)| name == stage) {
+                Some((_, total, count)) => {
+                    *total += spent;
+                    *count += 1;
+                }
+                None => totals.push((stage.clone(), spent, 1)),
+            }
+        }
+    }
+    totals
+        .into_iter()
+        .map(|(stage, total, count)| (stage, total / count as i32, count))
+        .collect()
+}
+
+pub fn format_duration(duration: TimeDelta) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Add `entry` to the frontmatter `history:` list, creating the frontmatter or list as needed
+pub fn add_history_entry(lines: &[String], entry: &str) -> Vec<String> {
+    let closing = (lines.first().map(String::as_str) == Some("---"))
+        .then(|| lines.iter().skip(1).position(|line| line == "---").map(|index| index + 1))
+        .flatten();
+
+    let mut result = lines.to_vec();
+    let Some(closing) = closing else {
+        let frontmatter = ["---".to_string(), "history:".to_string(), format!("  - {}", entry), "---".to_string()];
+        result.splice(0..0, frontmatter);
+        return result;
+    };
+    let Some(key) = (1..closing).find(|&index| lines[index].starts_with("history:")) else {
+        result.insert(closing, format!("  - {}", entry));
+        result.insert(closing, "history:".to_string());
+        return result;
+    };
+
+    let value = lines[key]["history:".len()..].trim();
+    if value == "[]" {
+        result[key] = "history:".to_string();
+        result.insert(key + 1, format!("  - {}", entry));
+    } else if let Some(items) = value.strip_prefix('[').and_then(|value| value.strip_suffix(']')) {
+        result[key] = format!("history: [{}, {}]", items.trim(), entry);
+    } else if value.is_empty() || value.starts_with('#') {
+        // Items may be indented or not; either way they run until the next top-level key
+        let end = (key + 1..closing)
+            .find(|&index| !lines[index].starts_with([' ', '\t', '-']))
+            .unwrap_or(closing);
+        let indent = lines[key + 1..end]
+            .iter()
+            .find_map(|line| line.trim_start().starts_with('-').then(|| &line[..line.len() - line.trim_start().len()]))
+            .unwrap_or("  ");
+        result.insert(end, format!("{}- {}", indent, entry));
+    }
+    // Any other value isn't a list, so it's left alone rather than turned into invalid YAML
+    result
+}
+
+impl<'a> App<'a> {
+    pub fn stage_name_of(&self, path: &Path) -> String {
+        self.stage_of(path)
+            .map(|index| self.stages[index].name.clone())
+            .unwrap_or_else(|| INBOX.to_string())
+    }
+
+    /// Log a note's move, and mirror it into the note's frontmatter if that's turned on
+    pub fn record_transition(&mut self, path: &Path, from: String, to: String) {
+        if from == to {
+            return;
+        }
+        let transition = Transition {
+            at: Local::now().naive_local(),
+            note: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            from,
+            to,
+        };
+        append_transition(&self.vault_root(), &transition);
+
+        if self.history_frontmatter {
+            let entry = format!("{} {} → {}", transition.at.format("%Y-%m-%d %H:%M"), transition.from, transition.to);
+            self.add_frontmatter_history(path, &entry);
+        }
+    }
+
+    fn add_frontmatter_history(&mut self, path: &Path, entry: &str) {
+        self.rewrite_note(path, |lines| add_history_entry(lines, entry));
+    }
+
+    pub fn open_timeline(&mut self) {
+        let journeys = journeys(load_transitions(&self.vault_root()));
+        let mut list_state = ListState::default();
+        if !journeys.is_empty() {
+            list_state.select(Some(0));
+        }
+        self.timeline = Some(TimelineState { journeys, list_state, show_report: false });
+        self.mode = Mode::Timeline;
+    }
+
+    pub fn close_timeline(&mut self) {
+        self.timeline = None;
+        self.mode = Mode::Normal;
+    }
+
+    pub fn timeline_select(&mut self, down: bool) {
+        let Some(timeline) = self.timeline.as_mut() else {
+            return;
+        };
+        let count = timeline.journeys.len();
+        if count == 0 {
+            return;
+        }
+        let current = timeline.list_state.selected().unwrap_or(0);
+        let next = if down { (current + 1) % count } else { (current + count - 1) % count };
+        timeline.list_state.select(Some(next));
+    }
+
+    pub fn toggle_timeline_report(&mut self) {
+        if let Some(timeline) = self.timeline.as_mut() {
+            timeline.show_report = !timeline.show_report;
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::{path::{Path, PathBuf}, process::{Command, Stdio}};
+
+use crate::app::App;
+use crate::modes::Mode;
+use crate::vault::VAULT_CONFIG;
+use crate::workflow::INBOX;
+
+/// Which side of a stage transition a hook runs on
+#[derive(Clone, Copy)]
+pub enum HookPhase {
+    Pre,
+    Post,
+}
+
+impl HookPhase {
+    fn label(self) -> &'static str {
+        match self {
+            HookPhase::Pre => "pre",
+            HookPhase::Post => "post",
+        }
+    }
+}
+
+/// Everything a hook is told about the transition, passed as `NOSTROMO_*` variables
+pub struct HookContext<'p> {
+    pub note: &'p Path,
+    pub destination: &'p Path,
+    pub from_stage: &'p str,
+    pub to_stage: &'p str,
+    pub root: &'p Path,
+}
+
+/// Run `command` through the shell, returning whether it succeeded and its output lines
+pub fn run_hook(command: &str, phase: HookPhase, context: &HookContext) -> (bool, Vec<String>) {
+    let mut log = vec![format!("$ {} ({}-hook for {})", command, phase.label(), context.to_stage)];
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(context.root)
+        .env("NOSTROMO_NOTE", context.note)
+        .env("NOSTROMO_NOTE_DEST", context.destination)
+        .env("NOSTROMO_FROM_STAGE", context.from_stage)
+        .env("NOSTROMO_TO_STAGE", context.to_stage)
+        .env("NOSTROMO_HOOK", phase.label())
+        .env("NOSTROMO_ROOT", context.root)
+        .stdin(Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) => {
+            log.extend(String::from_utf8_lossy(&output.stdout).lines().map(String::from));
+            log.extend(String::from_utf8_lossy(&output.stderr).lines().map(String::from));
+            match output.status.code() {
+                Some(0) => (true, log),
+                Some(code) => {
+                    log.push(format!("exited with status {}", code));
+                    (false, log)
+                }
+                None => {
+                    log.push("terminated by a signal".to_string());
+                    (false, log)
+                }
+            }
+        }
+        Err(error) => {
+            log.push(format!("failed to start: {}", error));
+            (false, log)
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    /// Move a note into a stage, running the stage's hooks around the move
+    pub fn move_to_stage(&mut self, path: &Path, stage: usize) -> Option<PathBuf> {
+        let target = self.stages.get(stage)?.clone();
+        let from_stage = self
+            .stage_of(path)
+            .map(|index| self.stages[index].name.clone())
+            .unwrap_or_else(|| INBOX.to_string());
+        let destination = target.folder.join(path.file_name()?);
+        let root = self.root.clone();
+        let mut log = Vec::new();
+
+        if (target.pre_hook.is_some() || target.post_hook.is_some()) && self.hooks_need_trust() {
+            let config = self.vault.as_deref().map(|vault| vault.join(VAULT_CONFIG).display().to_string()).unwrap_or_default();
+            if self.vault_hooks_changed() {
+                log.push(format!("{}'s hooks come from {}, whose hooks have changed since you trusted it", target.name, config));
+            } else {
+                log.push(format!("{}'s hooks come from {}, which isn't trusted yet", target.name, config));
+            }
+            log.push("Review them under Settings → Pipeline and trust the vault there to let them run".to_string());
+            log.push(format!("Move to {} cancelled", target.name));
+            self.show_hook_log(log);
+            return None;
+        }
+
+        if destination.exists() {
+            log.push(format!(
+                "{} already has a note called {}; move cancelled",
+                target.name,
+                destination.file_name().unwrap_or_default().to_string_lossy()
+            ));
+            self.show_hook_log(log);
+            return None;
+        }
+
+        if let Some(command) = &target.pre_hook {
+            let context = HookContext { note: path, destination: &destination, from_stage: &from_stage, to_stage: &target.name, root: &root };
+            let (succeeded, output) = run_hook(command, HookPhase::Pre, &context);
+            log.extend(output);
+            if !succeeded {
+                log.push(format!("Move to {} cancelled", target.name));
+                self.show_hook_log(log);
+                return None;
+            }
+        }
+
+        let moved = self.move_note(path, &target.folder);
+        if let (Some(new_path), Some(command)) = (&moved, &target.post_hook) {
+            let context = HookContext { note: new_path, destination: new_path, from_stage: &from_stage, to_stage: &target.name, root: &root };
+            log.extend(run_hook(command, HookPhase::Post, &context).1);
+        }
+
+        if !log.is_empty() {
+            self.show_hook_log(log);
+        }
+        moved
+    }
+
+    /// Show hook output, adding to the log if it's already open (as in a batch move)
+    fn show_hook_log(&mut self, log: Vec<String>) {
+        if self.mode != Mode::HookLog {
+            self.hook_log.clear();
+            self.hook_return_mode = self.mode;
+        }
+        self.hook_log.extend(log);
+        self.mode = Mode::HookLog;
+    }
+
+    pub fn close_hook_log(&mut self) {
+        self.hook_log.clear();
+        self.mode = self.hook_return_mode;
+    }
+}
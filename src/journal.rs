@@ -0,0 +1,167 @@
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+use std::{collections::{HashMap, HashSet}, fs, path::{Path, PathBuf}};
+
+use crate::app::App;
+use crate::modes::Mode;
+use crate::file_ops::IgnoreList;
+use crate::tasks::collect_open_tasks;
+use crate::templates::{expand_template, split_template_settings, TemplateContext};
+
+pub const JOURNAL_FOLDER: &str = "journal";
+
+/// The month grid shown by the calendar, with the days worth highlighting
+pub struct CalendarState {
+    pub selected: NaiveDate,
+    pub entries: HashSet<NaiveDate>,
+    pub open_tasks: HashSet<NaiveDate>,
+}
+
+pub fn journal_path(folder: &Path, date: NaiveDate) -> PathBuf {
+    folder.join(format!("{}.md", date.format("%Y-%m-%d")))
+}
+
+/// The date of a `journal/YYYY-MM-DD.md` note
+pub fn daily_note_date(path: &Path) -> Option<NaiveDate> {
+    let folder = path.parent()?.file_name()?;
+    if folder != JOURNAL_FOLDER || path.extension()? != "md" {
+        return None;
+    }
+    NaiveDate::parse_from_str(&path.file_stem()?.to_string_lossy(), "%Y-%m-%d").ok()
+}
+
+fn journal_entries(folder: &Path) -> HashSet<NaiveDate> {
+    fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| daily_note_date(&entry.path()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Days with an open task, either due then or written in that day's note
+fn open_task_days(root: &Path, ignore: &IgnoreList) -> HashSet<NaiveDate> {
+    collect_open_tasks(root, ignore)
+        .iter()
+        .flat_map(|task| {
+            let due = task.due.as_deref().and_then(|due| NaiveDate::parse_from_str(due, "%Y-%m-%d").ok());
+            due.into_iter().chain(daily_note_date(&task.path))
+        })
+        .collect()
+}
+
+impl<'a> App<'a> {
+    /// The vault's journal folder, wherever in the vault the file list is
+    pub fn journal_folder(&self) -> PathBuf {
+        self.vault_root().join(JOURNAL_FOLDER)
+    }
+
+    /// Open the daily note for `date`, creating it from the daily template if needed
+    pub fn open_daily_note(&mut self, date: NaiveDate) {
+        self.open_daily_note_in(&self.journal_folder(), date);
+    }
+
+    fn open_daily_note_in(&mut self, folder: &Path, date: NaiveDate) {
+        let path = journal_path(folder, date);
+        if !path.exists() {
+            let context = TemplateContext {
+                title: date.format("%Y-%m-%d").to_string(),
+                date: date.and_time(Local::now().time()),
+                root: self.root.clone(),
+            };
+            let content = self
+                .daily_template
+                .as_ref()
+                .and_then(|template| fs::read_to_string(template).ok())
+                .map(|template| expand_template(&split_template_settings(&template).1, &context, &HashMap::new()).text)
+                .unwrap_or_else(|| format!("# {}\n\n", date.format("%A, %B %-d, %Y")));
+            if let Some(folder) = path.parent() {
+                fs::create_dir_all(folder).ok();
+            }
+            fs::write(&path, content).ok();
+            self.load_files();
+        }
+        self.open_buffer(&path);
+    }
+
+    pub fn open_today(&mut self) {
+        self.open_daily_note(Local::now().date_naive());
+    }
+
+    /// Step from the daily note being edited to the previous or next existing entry
+    pub fn step_daily_note(&mut self, forward: bool) {
+        let Some((current, folder)) = self
+            .active_buffer()
+            .and_then(|buffer| Some((daily_note_date(&buffer.path)?, buffer.path.parent()?.to_path_buf())))
+        else {
+            return;
+        };
+        // Stay in the journal the note came from
+        let entries = journal_entries(&folder);
+        let target = if forward {
+            entries.iter().filter(|date| **date > current).min()
+        } else {
+            entries.iter().filter(|date| **date < current).max()
+        };
+        if let Some(date) = target.copied() {
+            self.save_active_buffer();
+            self.open_daily_note_in(&folder, date);
+        }
+    }
+
+    pub fn open_calendar(&mut self) {
+        let selected = self
+            .active_buffer()
+            .filter(|_| self.mode == Mode::Editing)
+            .and_then(|buffer| daily_note_date(&buffer.path))
+            .or_else(|| self.selected_file().and_then(|path| daily_note_date(path)))
+            .unwrap_or_else(|| Local::now().date_naive());
+        self.calendar = Some(CalendarState {
+            selected,
+            entries: journal_entries(&self.journal_folder()),
+            open_tasks: open_task_days(&self.vault_root(), &self.ignore),
+        });
+        self.mode = Mode::Calendar;
+    }
+
+    pub fn close_calendar(&mut self) {
+        self.calendar = None;
+        self.mode = Mode::Normal;
+    }
+
+    pub fn calendar_move_days(&mut self, days: i64) {
+        if let Some(calendar) = self.calendar.as_mut() {
+            calendar.selected += Duration::days(days);
+        }
+    }
+
+    pub fn calendar_move_months(&mut self, forward: bool) {
+        if let Some(calendar) = self.calendar.as_mut() {
+            let moved = if forward {
+                calendar.selected.checked_add_months(Months::new(1))
+            } else {
+                calendar.selected.checked_sub_months(Months::new(1))
+            };
+            calendar.selected = moved.unwrap_or(calendar.selected);
+        }
+    }
+
+    pub fn calendar_today(&mut self) {
+        if let Some(calendar) = self.calendar.as_mut() {
+            calendar.selected = Local::now().date_naive();
+        }
+    }
+
+    pub fn calendar_open_selected(&mut self) {
+        if let Some(calendar) = self.calendar.take() {
+            self.open_daily_note(calendar.selected);
+        }
+    }
+}
+
+/// First day shown in the grid: the Monday on or before the 1st of the month
+pub fn grid_start(date: NaiveDate) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date);
+    first - Duration::days(first.weekday().num_days_from_monday() as i64)
+}
@@ -0,0 +1,367 @@
+use std::fmt;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::actions::{Action, ACTIONS, DEFAULT_BINDINGS};
+use crate::app::App;
+use crate::config::Section;
+use crate::modes::Mode;
+
+/// A key press with its modifiers, as written in bindings like `Ctrl+S`, `Shift+K` or `Alt+[`
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+/// One or more chords pressed in turn (`g g`) that run `action` in `mode`
+pub struct KeyBinding {
+    pub mode: Mode,
+    pub keys: Vec<KeyChord>,
+    pub action: Action,
+}
+
+/// The live bindings: the defaults from `DEFAULT_BINDINGS` with any `[keys.<mode>]` overrides applied
+pub struct Keymap {
+    pub bindings: Vec<KeyBinding>,
+}
+
+enum KeyMatch {
+    Action(Action),
+    Prefix,
+    None,
+}
+
+impl KeyChord {
+    /// Characters carry their own case, so Shift is dropped from them to match `Shift+K` and `K` alike
+    pub fn from_event(key: KeyEvent) -> Self {
+        let modifiers = match key.code {
+            KeyCode::Char(_) => key.modifiers - KeyModifiers::SHIFT,
+            _ => key.modifiers,
+        };
+        Self { code: key.code, modifiers }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (modifier_names, key) = match s.rsplit_once('+') {
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').unwrap_or(modifiers), "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", s),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                // `Ctrl+S` means the s key; only an explicit Shift asks for the capital
+                let c = if modifiers.contains(KeyModifiers::SHIFT) {
+                    c.to_ascii_uppercase()
+                } else if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                    c.to_ascii_lowercase()
+                } else {
+                    c
+                };
+                modifiers -= KeyModifiers::SHIFT;
+                KeyCode::Char(c)
+            }
+            _ => match key.to_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "space" | "comma" => {
+                    modifiers -= KeyModifiers::SHIFT;
+                    KeyCode::Char(if key.eq_ignore_ascii_case("space") { ' ' } else { ',' })
+                }
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(Self { code, modifiers })
+    }
+
+    /// A bare character, which text-input modes treat as typing rather than a command
+    pub fn is_text(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(',') => write!(f, "Comma"),
+            KeyCode::Char(c) if c.is_ascii_uppercase() => write!(f, "Shift+{}", c),
+            KeyCode::Char(c) if !self.modifiers.is_empty() => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Chords separated by spaces, e.g. `g g` or `Ctrl+K Ctrl+S`
+pub fn parse_sequence(s: &str) -> Option<Vec<KeyChord>> {
+    let keys: Option<Vec<KeyChord>> = s.split_whitespace().map(KeyChord::parse).collect();
+    keys.filter(|keys| !keys.is_empty())
+}
+
+pub fn format_sequence(keys: &[KeyChord]) -> String {
+    keys.iter().map(|key| key.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+impl Keymap {
+    /// The default bindings with `[keys.<mode>]` overrides applied in file order
+    pub fn from_config(config: &Section, errors: &mut Vec<String>) -> Self {
+        let mut bindings = Vec::new();
+        for binding in DEFAULT_BINDINGS {
+            for mode in binding.modes {
+                for keys in binding.keys {
+                    if let Some(keys) = parse_sequence(keys) {
+                        bindings.push(KeyBinding { mode: *mode, keys, action: binding.action });
+                    }
+                }
+            }
+        }
+        let mut keymap = Self { bindings };
+
+        for mode_id in config.keys() {
+            let Some(mode) = Mode::ALL.into_iter().find(|mode| mode.id() == mode_id) else {
+                errors.push(format!("{}: unknown mode '{}'", config.key_path(mode_id), mode_id));
+                continue;
+            };
+            let overrides = config.section(mode_id, errors);
+            for action_id in overrides.keys() {
+                let Some(sequences) = overrides.strings(action_id, errors) else {
+                    continue;
+                };
+                if let Err(error) = keymap.apply_override(mode, action_id, &sequences) {
+                    errors.push(format!("{}: {}", overrides.key_path(action_id), error));
+                }
+            }
+        }
+        keymap
+    }
+
+    /// `<action> = ["<keys>", …]` replaces that action's keys in `mode`; an empty list unbinds it
+    fn apply_override(&mut self, mode: Mode, action_id: &str, sequences: &[&str]) -> Result<(), String> {
+        let action = ACTIONS
+            .iter()
+            .find(|spec| spec.id == action_id)
+            .map(|spec| spec.action)
+            .ok_or_else(|| format!("unknown action '{}'", action_id))?;
+
+        let mut parsed = Vec::new();
+        for keys in sequences {
+            parsed.push(parse_sequence(keys).ok_or_else(|| format!("can't read keys '{}'", keys))?);
+        }
+        self.bindings.retain(|binding| !(binding.mode == mode && binding.action == action));
+        self.bindings.extend(parsed.into_iter().map(|keys| KeyBinding { mode, keys, action }));
+        Ok(())
+    }
+
+    /// Keys for `action` in `mode`, leaving out ones that would be typed as text when `typing`
+    pub fn keys_for(&self, mode: Mode, action: Action, typing: bool) -> Vec<String> {
+        self.bindings
+            .iter()
+            .filter(|binding| binding.mode == mode && binding.action == action)
+            .filter(|binding| !(typing && binding.keys[0].is_text()))
+            .map(|binding| format_sequence(&binding.keys))
+            .collect()
+    }
+
+    /// The keys for `action` in `mode` joined for a hint, or `None` if it's unbound
+    pub fn key_hint(&self, mode: Mode, action: Action, typing: bool) -> Option<String> {
+        let keys = self.keys_for(mode, action, typing);
+        (!keys.is_empty()).then(|| keys.join("/"))
+    }
+}
+
+impl<'a> App<'a> {
+    fn match_keys(&self, keys: &[KeyChord]) -> KeyMatch {
+        let candidates = self
+            .keymap
+            .bindings
+            .iter()
+            .filter(|binding| binding.mode == self.mode && self.action_available(binding.action));
+        let mut is_prefix = false;
+        for binding in candidates {
+            if binding.keys == keys {
+                return KeyMatch::Action(binding.action);
+            }
+            is_prefix |= binding.keys.starts_with(keys);
+        }
+        if is_prefix { KeyMatch::Prefix } else { KeyMatch::None }
+    }
+
+    /// Run the action bound to `key`, waiting for the rest of a multi-key sequence; false when nothing is bound
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let chord = KeyChord::from_event(key);
+        if self.pending_keys.is_empty() && self.is_typing() && chord.is_text() {
+            return false;
+        }
+        self.pending_keys.push(chord);
+        match self.match_keys(&self.pending_keys) {
+            KeyMatch::Action(action) => {
+                self.pending_keys.clear();
+                self.run_action(action);
+                true
+            }
+            KeyMatch::Prefix => true,
+            KeyMatch::None if self.pending_keys.len() > 1 => {
+                // An abandoned sequence: start over from the latest key
+                self.pending_keys.clear();
+                self.handle_key(key)
+            }
+            KeyMatch::None => {
+                self.pending_keys.clear();
+                false
+            }
+        }
+    }
+
+    /// The controls line for the current mode, built from the live keymap
+    pub fn controls_line(&self) -> String {
+        if !self.pending_keys.is_empty() {
+            return format!("▶ KEYS: {} …", format_sequence(&self.pending_keys));
+        }
+        self.mode_actions(self.mode, self.is_typing())
+            .iter()
+            .map(|(action, keys)| format!("▶ {}: {}", action.title().to_uppercase(), keys.join("/")))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn open_help(&mut self) {
+        if self.mode != Mode::Help {
+            self.help_return_mode = self.mode;
+            self.help_scroll = 0;
+            self.mode = Mode::Help;
+        }
+    }
+
+    pub fn close_help(&mut self) {
+        self.mode = self.help_return_mode;
+    }
+
+    pub fn scroll_help(&mut self, lines: i32) {
+        self.help_scroll = self.help_scroll.saturating_add_signed(lines as i16);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(code: KeyCode, modifiers: KeyModifiers) -> Option<KeyChord> {
+        Some(KeyChord { code, modifiers })
+    }
+
+    #[test]
+    fn parses_plain_and_named_keys() {
+        assert!(KeyChord::parse("q") == chord(KeyCode::Char('q'), KeyModifiers::NONE));
+        assert!(KeyChord::parse(" Enter ") == chord(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(KeyChord::parse("esc") == chord(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(KeyChord::parse("PageDown") == chord(KeyCode::PageDown, KeyModifiers::NONE));
+        assert!(KeyChord::parse("F5") == chord(KeyCode::F(5), KeyModifiers::NONE));
+        assert!(KeyChord::parse("f12") == chord(KeyCode::F(12), KeyModifiers::NONE));
+        assert!(KeyChord::parse("Space") == chord(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert!(KeyChord::parse("Comma") == chord(KeyCode::Char(','), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn letters_with_ctrl_or_alt_match_however_they_are_written() {
+        let ctrl_s = chord(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert!(KeyChord::parse("Ctrl+S") == ctrl_s);
+        assert!(KeyChord::parse("ctrl+s") == ctrl_s);
+        assert!(KeyChord::parse("Control+S") == ctrl_s);
+        assert!(KeyChord::parse("Alt+X") == chord(KeyCode::Char('x'), KeyModifiers::ALT));
+        assert!(KeyChord::parse("Meta+x") == chord(KeyCode::Char('x'), KeyModifiers::ALT));
+        assert!(KeyChord::parse("Ctrl+Alt+Enter") == chord(KeyCode::Enter, KeyModifiers::CONTROL | KeyModifiers::ALT));
+    }
+
+    #[test]
+    fn shift_on_a_character_becomes_its_capital() {
+        let capital_k = chord(KeyCode::Char('K'), KeyModifiers::NONE);
+        assert!(KeyChord::parse("Shift+k") == capital_k);
+        assert!(KeyChord::parse("Shift+K") == capital_k);
+        assert!(KeyChord::parse("K") == capital_k);
+        assert!(KeyChord::parse("Ctrl+Shift+s") == chord(KeyCode::Char('S'), KeyModifiers::CONTROL));
+        assert!(KeyChord::parse("Shift+Space") == chord(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert!(KeyChord::parse("Shift+Tab") == chord(KeyCode::Tab, KeyModifiers::SHIFT));
+    }
+
+    #[test]
+    fn plus_can_be_the_key_itself() {
+        assert!(KeyChord::parse("+") == chord(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert!(KeyChord::parse("Ctrl++") == chord(KeyCode::Char('+'), KeyModifiers::CONTROL));
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        assert!(KeyChord::parse("Hyper+x").is_none());
+        assert!(KeyChord::parse("Enterr").is_none());
+        assert!(KeyChord::parse("Fx").is_none());
+        assert!(KeyChord::parse("").is_none());
+    }
+
+    #[test]
+    fn matches_key_events_the_terminal_sends() {
+        let event = |code, modifiers| KeyChord::from_event(KeyEvent::new(code, modifiers));
+        assert!(KeyChord::parse("Shift+K") == Some(event(KeyCode::Char('K'), KeyModifiers::SHIFT)));
+        assert!(KeyChord::parse("Ctrl+S") == Some(event(KeyCode::Char('s'), KeyModifiers::CONTROL)));
+        assert!(KeyChord::parse("Shift+Tab") == Some(event(KeyCode::Tab, KeyModifiers::SHIFT)));
+    }
+
+    #[test]
+    fn formats_what_it_parses() {
+        for text in ["q", "Shift+K", "Ctrl+S", "Alt+Enter", "Space", "Comma", "F5", "PgUp"] {
+            let parsed = KeyChord::parse(text).expect("parses");
+            assert_eq!(parsed.to_string(), text);
+            assert!(KeyChord::parse(&parsed.to_string()) == Some(parsed));
+        }
+    }
+
+    #[test]
+    fn parses_sequences() {
+        let keys = parse_sequence("g  g").expect("two chords");
+        assert_eq!(format_sequence(&keys), "g g");
+        assert_eq!(parse_sequence("Ctrl+K Ctrl+S").map(|keys| keys.len()), Some(2));
+        assert!(parse_sequence("").is_none());
+        assert!(parse_sequence("g Bogus").is_none());
+    }
+}
@@ -1,18 +1,41 @@
 use ratatui::crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
-use std::{error::Error, fs, io};
+use std::{env, error::Error, fs, io, process};
 
+mod actions;
 mod app;
 mod app_methods;
+mod batch;
+mod board;
+mod bookmarks;
+mod buffers;
+mod bulk_rename;
 mod clipboard;
 mod config;
 mod constants;
 mod file_ops;
+mod file_sort;
+mod highlight;
+mod history;
+mod hooks;
+mod journal;
+mod keymap;
+mod find;
 mod modes;
+mod editor_options;
+mod outline;
+mod palette;
+mod preview;
+mod reflow;
+mod split;
+mod tasks;
+mod templates;
+mod workflow;
+mod vault;
 mod ui;
 
 use app::App;
@@ -23,17 +46,54 @@ use ui::ui;
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
 fn main() -> Result<()> {
+    let vault = parse_args();
     if fs::metadata("welcome.md").is_err() {
         fs::write("welcome.md", WELCOME_FILE_CONTENT)?;
     }
 
-    let mut terminal = setup_terminal()?;
     let mut app = App::new();
+    if let Some(name) = vault {
+        open_named_vault(&mut app, &name);
+    }
+
+    let mut terminal = setup_terminal()?;
     run(&mut terminal, &mut app)?;
     restore_terminal(&mut terminal)?;
+    app.remember_vault_session();
+    app.save_config();
     Ok(())
 }
 
+/// The vault named by `--vault <name>`, if given
+fn parse_args() -> Option<String> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => None,
+        [flag, name] if flag == "--vault" => Some(name.clone()),
+        _ => {
+            eprintln!("usage: nostromo-notes [--vault <name>]");
+            process::exit(2);
+        }
+    }
+}
+
+fn open_named_vault(app: &mut App, name: &str) {
+    let Some(index) = app.vaults.iter().position(|vault| vault.name == name) else {
+        let names: Vec<&str> = app.vaults.iter().map(|vault| vault.name.as_str()).collect();
+        if names.is_empty() {
+            eprintln!("no vault named '{}': config.toml has no [[vaults]] entries", name);
+        } else {
+            eprintln!("no vault named '{}'; known vaults: {}", name, names.join(", "));
+        }
+        process::exit(2);
+    };
+    // Nothing is open yet, so there are no unsaved notes to refuse over
+    app.switch_vault(index).ok();
+    if !app.config_errors.is_empty() {
+        app.mode = Mode::ConfigErrors;
+    }
+}
+
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     let mut stdout = io::stdout();
     enable_raw_mode()?;
@@ -60,6 +120,8 @@ fn run<'a>(
 ) -> Result<()> {
     loop {
         app.update_timing();
+        app.refresh_preview();
+        app.refresh_outline();
         terminal.draw(|f| ui(f, app))?;
 
         if let Event::Key(key) = event::read()? {
@@ -67,141 +129,56 @@ fn run<'a>(
                 continue;
             }
 
-            let current_mode = app.mode;
-            match current_mode {
-                Mode::Normal => match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Char('n') => app.mode = Mode::Naming,
-                    KeyCode::Char('r') => app.start_rename(),
-                    KeyCode::Char('c') => app.enter_directory_browser(false),
-                    KeyCode::Char('T') => app.start_template_workflow(),
-                    KeyCode::Char('/') => app.enter_search_mode(),
-                    KeyCode::Char('s') => app.enter_settings(),
-                    KeyCode::Char('d') => app.start_delete_confirmation(),
-                    KeyCode::Char('m') => app.start_move_selection(),
-                    KeyCode::Down => app.select_next(),
-                    KeyCode::Up => app.select_previous(),
-                    KeyCode::Left => app.navigate_up_directory(),
-                    KeyCode::Right | KeyCode::Enter => app.start_editing(),
-                    _ => {}
-                },
-                Mode::Editing => match key.code {
-                    KeyCode::Esc => app.stop_editing(),
-                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        app.copy_file_to_clipboard();
-                    }
-                    _ => {
-                        if let Some(editor) = app.editor.as_mut() {
+            if !app.handle_key(key) {
+                // Keys without a binding are text input in the modes that take it
+                match (app.mode, key.code) {
+                    (Mode::Editing, _) => {
+                        if let Some(editor) = app.active_editor_mut() {
                             editor.input(key);
                         }
                     }
-                },
-                Mode::Naming => match key.code {
-                    KeyCode::Enter => app.create_new_note(),
-                    KeyCode::Esc => {
-                        app.filename_input.clear();
-                        app.pending_template = None;
-                        app.mode = Mode::Normal;
-                    }
-                    KeyCode::Char(c) => {
+                    (Mode::Finding, KeyCode::Char(c)) => app.find_input_char(c),
+                    (Mode::Finding, KeyCode::Backspace) => app.find_backspace(),
+                    (Mode::PreviewFind, KeyCode::Char(c)) => app.preview_find_input(c),
+                    (Mode::PreviewFind, KeyCode::Backspace) => app.preview_find_backspace(),
+                    (Mode::Naming | Mode::Renaming | Mode::Tagging | Mode::EditingStage, KeyCode::Char(c)) => {
                         app.filename_input.push(c);
                     }
-                    KeyCode::Backspace => {
+                    (Mode::Naming | Mode::Renaming | Mode::Tagging | Mode::EditingStage, KeyCode::Backspace) => {
                         app.filename_input.pop();
                     }
-                    _ => {}
-                },
-                Mode::Renaming => match key.code {
-                    KeyCode::Enter => app.execute_rename(),
-                    KeyCode::Esc => app.cancel_rename(),
-                    KeyCode::Char(c) => {
-                        app.filename_input.push(c);
-                    }
-                    KeyCode::Backspace => {
-                        app.filename_input.pop();
-                    }
-                    _ => {}
-                },
-                Mode::ChangingDirectory => match key.code {
-                    KeyCode::Esc => app.mode = Mode::Normal,
-                    KeyCode::Char('s') => app.set_new_root(),
-                    KeyCode::Enter => app.select_browser_entry(),
-                    KeyCode::Down => app.select_next(),
-                    KeyCode::Up => app.select_previous(),
-                    _ => {}
-                },
-                Mode::SelectingTemplateFolder => match key.code {
-                    KeyCode::Esc => app.mode = Mode::Normal,
-                    KeyCode::Char('s') => app.set_template_root(),
-                    KeyCode::Enter => app.select_browser_entry(),
-                    KeyCode::Down => app.select_next(),
-                    KeyCode::Up => app.select_previous(),
-                    _ => {}
-                },
-                Mode::SelectingTemplate => match key.code {
-                    KeyCode::Esc => app.mode = Mode::Normal,
-                    KeyCode::Enter => app.select_template(),
-                    KeyCode::Down => app.select_next(),
-                    KeyCode::Up => app.select_previous(),
-                    _ => {}
-                },
-                Mode::Search => {
-                    if app.search_input_mode {
-                        // Input mode: typing search query
-                        match key.code {
-                            KeyCode::Esc => app.exit_search_mode(),
-                            KeyCode::Enter => app.toggle_search_mode(), // Switch to navigation mode
-                            KeyCode::Char(c) => {
-                                app.search_input.push(c);
-                                app.update_filtered_files();
-                            }
-                            KeyCode::Backspace => {
-                                app.search_input.pop();
-                                app.update_filtered_files();
-                            }
-                            _ => {}
+                    (Mode::TemplatePrompt, KeyCode::Char(c)) => {
+                        if let Some(pending) = app.pending_note.as_mut() {
+                            pending.input.push(c);
                         }
-                    } else {
-                        // Navigation mode: can navigate and perform actions on filtered results
-                        match key.code {
-                            KeyCode::Esc => app.exit_search_mode(),
-                            KeyCode::Right | KeyCode::Enter => app.start_editing(),
-                            KeyCode::Down => app.select_next(),
-                            KeyCode::Up => app.select_previous(),
-                            KeyCode::Left => app.navigate_up_directory(),
-                            KeyCode::Char('d') => app.start_delete_confirmation(),
-                            KeyCode::Char('m') => app.start_move_selection(),
-                            KeyCode::Char('r') => app.start_rename(),
-                            KeyCode::Char('/') => {
-                                // Go back to input mode to modify search
-                                app.search_input_mode = true;
-                            }
-                            _ => {}
+                    }
+                    (Mode::TemplatePrompt, KeyCode::Backspace) => {
+                        if let Some(pending) = app.pending_note.as_mut() {
+                            pending.input.pop();
                         }
                     }
-                },
-                Mode::ConfirmingDelete => match key.code {
-                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => app.confirm_delete(),
-                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => app.cancel_operation(),
-                    _ => {}
-                },
-                Mode::SelectingMoveDestination => match key.code {
-                    KeyCode::Esc => app.cancel_operation(),
-                    KeyCode::Enter => app.execute_move(),
-                    KeyCode::Down => app.move_selection_next(),
-                    KeyCode::Up => app.move_selection_previous(),
-                    _ => {}
-                },
-                Mode::Settings => match key.code {
-                    KeyCode::Esc => app.exit_settings(),
-                    KeyCode::Enter => {
-                        app.apply_color_scheme();
-                        app.exit_settings();
+                    (Mode::Switcher, KeyCode::Char(c)) => app.switcher_input(c),
+                    (Mode::Switcher, KeyCode::Backspace) => app.switcher_backspace(),
+                    (Mode::BulkRename, KeyCode::Char(c)) => app.bulk_rename_input(c),
+                    (Mode::BulkRename, KeyCode::Backspace) => app.bulk_rename_backspace(),
+                    (Mode::SelectingTemplate, KeyCode::Char(c)) => app.template_filter_input(c),
+                    (Mode::SelectingTemplate, KeyCode::Backspace) => app.template_filter_backspace(),
+                    (Mode::CommandPalette, KeyCode::Char(c)) => app.palette_input(c),
+                    (Mode::CommandPalette, KeyCode::Backspace) => app.palette_backspace(),
+                    (Mode::Search, KeyCode::Char(c)) if app.search_input_mode => {
+                        app.search_input.push(c);
+                        app.update_filtered_files();
+                    }
+                    (Mode::Search, KeyCode::Backspace) if app.search_input_mode => {
+                        app.search_input.pop();
+                        app.update_filtered_files();
                     }
-                    KeyCode::Down => app.settings_next(),
-                    KeyCode::Up => app.settings_previous(),
                     _ => {}
-                },
+                }
+            }
+
+            if app.should_quit {
+                return Ok(());
             }
         }
     }
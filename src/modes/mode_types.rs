@@ -2,31 +2,138 @@
 pub enum Mode {
     Normal,
     Editing,
+    Finding,
+    Outline,
+    PreviewFind,
+    Tasks,
+    Calendar,
+    Board,
+    HookLog,
+    Timeline,
     Naming,
+    TemplatePrompt,
     Renaming,
+    BulkRename,
+    Switcher,
+    VaultSwitcher,
     ChangingDirectory,
     SelectingTemplateFolder,
     SelectingTemplate,
     Search,
     ConfirmingDelete,
+    ConfirmingQuit,
+    ConfirmingBatch,
+    Tagging,
     SelectingMoveDestination,
     Settings,
+    EditingStage,
+    CommandPalette,
+    Help,
+    ConfigErrors,
 }
 
 impl Mode {
+    pub const ALL: [Mode; 30] = [
+        Mode::Normal,
+        Mode::Editing,
+        Mode::Finding,
+        Mode::Outline,
+        Mode::PreviewFind,
+        Mode::Tasks,
+        Mode::Calendar,
+        Mode::Board,
+        Mode::HookLog,
+        Mode::Timeline,
+        Mode::Naming,
+        Mode::TemplatePrompt,
+        Mode::Renaming,
+        Mode::BulkRename,
+        Mode::Switcher,
+        Mode::VaultSwitcher,
+        Mode::ChangingDirectory,
+        Mode::SelectingTemplateFolder,
+        Mode::SelectingTemplate,
+        Mode::Search,
+        Mode::ConfirmingDelete,
+        Mode::ConfirmingQuit,
+        Mode::ConfirmingBatch,
+        Mode::Tagging,
+        Mode::SelectingMoveDestination,
+        Mode::Settings,
+        Mode::EditingStage,
+        Mode::CommandPalette,
+        Mode::Help,
+        Mode::ConfigErrors,
+    ];
+
     pub fn to_string(&self) -> &str {
         match self {
             Mode::Normal => "NAVIGATE",
             Mode::Editing => "EDITING",
+            Mode::Finding => "FIND/REPLACE",
+            Mode::Outline => "OUTLINE",
+            Mode::PreviewFind => "PREVIEW FIND",
+            Mode::Tasks => "TASKS",
+            Mode::Calendar => "CALENDAR",
+            Mode::Board => "BOARD",
+            Mode::HookLog => "HOOK LOG",
+            Mode::Timeline => "TIMELINE",
             Mode::Naming => "NAMING",
+            Mode::TemplatePrompt => "TEMPLATE PROMPT",
             Mode::Renaming => "RENAMING",
+            Mode::BulkRename => "BULK RENAME",
+            Mode::Switcher => "QUICK SWITCH",
+            Mode::VaultSwitcher => "VAULTS",
             Mode::ChangingDirectory => "CHANGE DIR",
             Mode::SelectingTemplateFolder => "SELECT TMPL DIR",
             Mode::SelectingTemplate => "SELECT TMPL",
             Mode::Search => "SEARCH",
             Mode::ConfirmingDelete => "CONFIRM DELETE",
+            Mode::ConfirmingQuit => "CONFIRM QUIT",
+            Mode::ConfirmingBatch => "CONFIRM BATCH",
+            Mode::Tagging => "TAGGING",
             Mode::SelectingMoveDestination => "SELECT MOVE DEST",
             Mode::Settings => "SETTINGS",
+            Mode::EditingStage => "EDIT STAGE",
+            Mode::CommandPalette => "COMMAND PALETTE",
+            Mode::Help => "HELP",
+            Mode::ConfigErrors => "CONFIG ERRORS",
+        }
+    }
+
+    /// Name used for this mode's `[keys.<mode>]` config table
+    pub fn id(&self) -> &'static str {
+        match self {
+            Mode::Normal => "normal",
+            Mode::Editing => "editing",
+            Mode::Finding => "finding",
+            Mode::Outline => "outline",
+            Mode::PreviewFind => "preview_find",
+            Mode::Tasks => "tasks",
+            Mode::Calendar => "calendar",
+            Mode::Board => "board",
+            Mode::HookLog => "hook_log",
+            Mode::Timeline => "timeline",
+            Mode::Naming => "naming",
+            Mode::TemplatePrompt => "template_prompt",
+            Mode::Renaming => "renaming",
+            Mode::BulkRename => "bulk_rename",
+            Mode::Switcher => "switcher",
+            Mode::VaultSwitcher => "vault_switcher",
+            Mode::ChangingDirectory => "changing_directory",
+            Mode::SelectingTemplateFolder => "selecting_template_folder",
+            Mode::SelectingTemplate => "selecting_template",
+            Mode::Search => "search",
+            Mode::ConfirmingDelete => "confirming_delete",
+            Mode::ConfirmingQuit => "confirming_quit",
+            Mode::ConfirmingBatch => "confirming_batch",
+            Mode::Tagging => "tagging",
+            Mode::SelectingMoveDestination => "selecting_move_destination",
+            Mode::Settings => "settings",
+            Mode::EditingStage => "editing_stage",
+            Mode::CommandPalette => "command_palette",
+            Mode::Help => "help",
+            Mode::ConfigErrors => "config_errors",
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,152 @@
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use tui_textarea::CursorMove;
+
+use crate::app::App;
+use crate::modes::Mode;
+
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    pub line: usize,
+}
+
+/// Collect every heading in a markdown document along with the line it starts on
+pub fn parse_headings(text: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut current: Option<Heading> = None;
+
+    for (event, range) in Parser::new_ext(text, Options::ENABLE_YAML_STYLE_METADATA_BLOCKS).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some(Heading {
+                    level: level as usize,
+                    title: String::new(),
+                    line: text[..range.start].matches('\n').count(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.title.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(heading) = current.take() {
+                    headings.push(heading);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+impl<'a> App<'a> {
+    /// Note the outline describes: the open buffer while editing, otherwise the previewed file
+    fn outline_source(&self) -> Option<String> {
+        if self.editor_visible() {
+            return self.active_editor().map(|editor| editor.lines().join("\n"));
+        }
+        self.preview_cache.as_ref().map(|cache| cache.lines.join("\n"))
+    }
+
+    pub fn editor_visible(&self) -> bool {
+        match self.mode {
+            Mode::Outline => matches!(self.outline_return_mode, Mode::Editing | Mode::Finding),
+            _ => matches!(self.mode, Mode::Editing | Mode::Finding),
+        }
+    }
+
+    pub fn outline_visible(&self) -> bool {
+        self.mode == Mode::Outline || (self.show_outline && self.editor_visible())
+    }
+
+    /// Line of the note the user is currently looking at
+    fn outline_position(&self) -> usize {
+        if self.editor_visible() {
+            self.active_editor().map(|editor| editor.cursor().0).unwrap_or(0)
+        } else {
+            self.current_preview_scroll()
+        }
+    }
+
+    /// Index of the heading whose section contains the current position
+    pub fn current_section(&self) -> Option<usize> {
+        let position = self.outline_position();
+        self.outline.iter().rposition(|heading| heading.line <= position)
+    }
+
+    pub fn refresh_outline(&mut self) {
+        if !self.outline_visible() {
+            return;
+        }
+        self.outline = self.outline_source().map(|text| parse_headings(&text)).unwrap_or_default();
+        if self.mode != Mode::Outline {
+            self.outline_state.select(self.current_section());
+        }
+    }
+
+    pub fn open_outline(&mut self) {
+        if self.mode == Mode::Outline {
+            return;
+        }
+        self.outline_return_mode = self.mode;
+        self.show_outline = true;
+        self.mode = Mode::Outline;
+        self.outline = self.outline_source().map(|text| parse_headings(&text)).unwrap_or_default();
+        self.outline_state.select(self.current_section().or(if self.outline.is_empty() { None } else { Some(0) }));
+    }
+
+    pub fn close_outline(&mut self) {
+        self.mode = self.outline_return_mode;
+    }
+
+    pub fn hide_outline(&mut self) {
+        self.show_outline = false;
+        self.close_outline();
+    }
+
+    pub fn outline_next(&mut self) {
+        if self.outline.is_empty() {
+            return;
+        }
+        let i = match self.outline_state.selected() {
+            Some(i) if i + 1 < self.outline.len() => i + 1,
+            _ => 0,
+        };
+        self.outline_state.select(Some(i));
+    }
+
+    pub fn outline_previous(&mut self) {
+        if self.outline.is_empty() {
+            return;
+        }
+        let i = match self.outline_state.selected() {
+            Some(0) | None => self.outline.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.outline_state.select(Some(i));
+    }
+
+    /// Move the editor cursor or the preview scroll to the selected heading
+    pub fn jump_to_heading(&mut self) {
+        let Some(line) = self
+            .outline_state
+            .selected()
+            .and_then(|index| self.outline.get(index))
+            .map(|heading| heading.line)
+        else {
+            self.close_outline();
+            return;
+        };
+
+        if self.editor_visible() {
+            if let Some(editor) = self.active_editor_mut() {
+                editor.move_cursor(CursorMove::Jump(line as u16, 0));
+            }
+        } else if let Some(path) = self.selected_file().cloned() {
+            self.preview_scroll.insert(path, line);
+        }
+        self.close_outline();
+    }
+}
@@ -0,0 +1,107 @@
+use fuzzy_matcher::FuzzyMatcher;
+use ratatui::widgets::ListState;
+
+use crate::actions::Action;
+use crate::app::App;
+use crate::modes::Mode;
+
+pub struct PaletteEntry {
+    pub action: Action,
+    pub keys: String,
+}
+
+pub struct PaletteState {
+    pub filter: String,
+    pub return_mode: Mode,
+    pub entries: Vec<PaletteEntry>,
+    pub list_state: ListState,
+}
+
+impl<'a> App<'a> {
+    pub fn open_palette(&mut self) {
+        if self.mode == Mode::CommandPalette {
+            return;
+        }
+        self.palette = Some(PaletteState {
+            filter: String::new(),
+            return_mode: self.mode,
+            entries: Vec::new(),
+            list_state: ListState::default(),
+        });
+        self.mode = Mode::CommandPalette;
+        self.update_palette();
+    }
+
+    pub fn close_palette(&mut self) {
+        if let Some(palette) = self.palette.take() {
+            self.mode = palette.return_mode;
+        }
+    }
+
+    /// Actions of the mode the palette was opened from, fuzzy-matched on title and id
+    fn update_palette(&mut self) {
+        let Some(return_mode) = self.palette.as_ref().map(|palette| palette.return_mode) else {
+            return;
+        };
+        let actions = self.mode_actions(return_mode, false);
+        let Some(palette) = self.palette.as_mut() else {
+            return;
+        };
+        let mut scored: Vec<(i64, PaletteEntry)> = actions
+            .into_iter()
+            .filter(|(action, _)| *action != Action::OpenPalette)
+            .filter_map(|(action, keys)| {
+                let score = if palette.filter.is_empty() {
+                    0
+                } else {
+                    let text = format!("{} {}", action.title(), action.id());
+                    self.fuzzy_matcher.fuzzy_match(&text, &palette.filter)?
+                };
+                Some((score, PaletteEntry { action, keys: keys.join(" / ") }))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        palette.entries = scored.into_iter().map(|(_, entry)| entry).collect();
+        palette.list_state.select(if palette.entries.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn palette_input(&mut self, c: char) {
+        if let Some(palette) = self.palette.as_mut() {
+            palette.filter.push(c);
+        }
+        self.update_palette();
+    }
+
+    pub fn palette_backspace(&mut self) {
+        if let Some(palette) = self.palette.as_mut() {
+            palette.filter.pop();
+        }
+        self.update_palette();
+    }
+
+    pub fn palette_select(&mut self, forward: bool) {
+        let Some(palette) = self.palette.as_mut() else {
+            return;
+        };
+        let count = palette.entries.len();
+        if count == 0 {
+            return;
+        }
+        let current = palette.list_state.selected().unwrap_or(0);
+        let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
+        palette.list_state.select(Some(next));
+    }
+
+    /// Close the palette and run the highlighted action in the mode it was opened from
+    pub fn run_palette_entry(&mut self) {
+        let action = self
+            .palette
+            .as_ref()
+            .and_then(|palette| palette.entries.get(palette.list_state.selected()?))
+            .map(|entry| entry.action);
+        self.close_palette();
+        if let Some(action) = action {
+            self.run_action(action);
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use crate::app::App;
+use crate::modes::Mode;
+
+/// Lines of the previewed file, re-read only when the selection or the file's mtime changes
+pub struct PreviewCache {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub lines: Vec<String>,
+}
+
+impl<'a> App<'a> {
+    pub fn refresh_preview(&mut self) {
+        let Some(path) = self.selected_file().filter(|path| path.is_file()).cloned() else {
+            self.preview_cache = None;
+            return;
+        };
+        let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+        let is_fresh = self
+            .preview_cache
+            .as_ref()
+            .is_some_and(|cache| cache.path == path && cache.modified == modified);
+        if is_fresh {
+            return;
+        }
+
+        let lines = fs::read_to_string(&path)
+            .map(|content| content.lines().map(String::from).collect())
+            .unwrap_or_default();
+        self.preview_cache = Some(PreviewCache { path, modified, lines });
+    }
+
+    pub fn preview_lines(&self) -> &[String] {
+        self.preview_cache.as_ref().map(|cache| cache.lines.as_slice()).unwrap_or_default()
+    }
+
+    pub fn current_preview_scroll(&self) -> usize {
+        self.selected_file()
+            .and_then(|path| self.preview_scroll.get(path))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn set_preview_scroll(&mut self, scroll: usize) {
+        let max_scroll = self.preview_lines().len().saturating_sub(1);
+        if let Some(path) = self.selected_file().cloned() {
+            self.preview_scroll.insert(path, scroll.min(max_scroll));
+        }
+    }
+
+    pub fn scroll_preview(&mut self, delta: i32) {
+        let scroll = self.current_preview_scroll().saturating_add_signed(delta as isize);
+        self.set_preview_scroll(scroll);
+    }
+
+    pub fn preview_page_down(&mut self) {
+        self.scroll_preview(self.preview_height.max(1) as i32);
+    }
+
+    pub fn preview_page_up(&mut self) {
+        self.scroll_preview(-(self.preview_height.max(1) as i32));
+    }
+
+    pub fn preview_half_page_down(&mut self) {
+        self.scroll_preview((self.preview_height / 2).max(1) as i32);
+    }
+
+    pub fn preview_half_page_up(&mut self) {
+        self.scroll_preview(-((self.preview_height / 2).max(1) as i32));
+    }
+
+    pub fn enter_preview_find(&mut self) {
+        if self.preview_cache.is_some() {
+            self.preview_find.clear();
+            self.mode = Mode::PreviewFind;
+        }
+    }
+
+    pub fn exit_preview_find(&mut self) {
+        self.preview_find.clear();
+        self.mode = Mode::Normal;
+    }
+
+    pub fn preview_find_input(&mut self, c: char) {
+        self.preview_find.push(c);
+        self.jump_to_preview_match(0, true);
+    }
+
+    pub fn preview_find_backspace(&mut self) {
+        self.preview_find.pop();
+        self.jump_to_preview_match(0, true);
+    }
+
+    pub fn preview_find_next(&mut self) {
+        self.jump_to_preview_match(1, true);
+    }
+
+    pub fn preview_find_previous(&mut self) {
+        self.jump_to_preview_match(1, false);
+    }
+
+    /// Scroll to the nearest line containing the query, starting `offset` lines from the top of the view
+    fn jump_to_preview_match(&mut self, offset: usize, forward: bool) {
+        if self.preview_find.is_empty() {
+            return;
+        }
+        let query = self.preview_find.to_lowercase();
+        let lines = self.preview_lines();
+        let count = lines.len();
+        if count == 0 {
+            return;
+        }
+
+        let start = self.current_preview_scroll();
+        let found = (0..count)
+            .map(|step| {
+                let step = step + offset;
+                if forward {
+                    (start + step) % count
+                } else {
+                    (start + count * 2 - step) % count
+                }
+            })
+            .find(|&index| lines[index].to_lowercase().contains(&query));
+
+        if let Some(index) = found {
+            self.set_preview_scroll(index);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use tui_textarea::CursorMove;
+
+use crate::app::App;
+
+/// Leading markup of a line that reflow must keep: block quote markers plus any list marker
+struct LinePrefix {
+    quote: String,
+    marker: String,
+}
+
+fn split_prefix(line: &str) -> (LinePrefix, &str) {
+    let mut rest = line;
+    let mut quote = String::new();
+    loop {
+        let trimmed = rest.trim_start();
+        if let Some(after) = trimmed.strip_prefix('>') {
+            quote.push_str(&rest[..rest.len() - trimmed.len()]);
+            quote.push('>');
+            let after_space = after.strip_prefix(' ').unwrap_or(after);
+            if after_space.len() != after.len() {
+                quote.push(' ');
+            }
+            rest = after_space;
+        } else {
+            break;
+        }
+    }
+
+    let indent_len = rest.len() - rest.trim_start().len();
+    let body = &rest[indent_len..];
+    let marker_len = list_marker_len(body);
+    let marker = rest[..indent_len + marker_len].to_string();
+    (LinePrefix { quote, marker }, &rest[indent_len + marker_len..])
+}
+
+/// Length of a `- `, `* `, `+ ` or `1. ` marker (with an optional task checkbox) at the start of `body`
+fn list_marker_len(body: &str) -> usize {
+    let bullet = if body.starts_with("- ") || body.starts_with("* ") || body.starts_with("+ ") {
+        2
+    } else {
+        let digits = body.chars().take_while(|c| c.is_ascii_digit()).count();
+        let after = &body[digits..];
+        if digits > 0 && (after.starts_with(". ") || after.starts_with(") ")) {
+            digits + 2
+        } else {
+            return 0;
+        }
+    };
+    let checkbox = ["[ ] ", "[x] ", "[X] "].iter().find(|checkbox| body[bullet..].starts_with(*checkbox));
+    bullet + checkbox.map(|checkbox| checkbox.len()).unwrap_or(0)
+}
+
+/// Lines that always stand alone and end a paragraph
+fn is_block_boundary(body: &str, prefix: &LinePrefix) -> bool {
+    let trimmed = body.trim();
+    prefix.marker.trim().is_empty() && trimmed.is_empty()
+        || trimmed.starts_with('#')
+        || trimmed.starts_with("```")
+        || trimmed.starts_with("~~~")
+        || trimmed.starts_with('|')
+        || trimmed == "---"
+}
+
+/// Row range of the paragraph containing `row`
+fn paragraph_bounds(lines: &[String], row: usize) -> Option<(usize, usize)> {
+    let (prefix, body) = split_prefix(&lines[row]);
+    if is_block_boundary(body, &prefix) {
+        return None;
+    }
+
+    // A list marker starts its own paragraph; otherwise walk back to the item or paragraph start
+    let mut start = row;
+    while start > 0 && prefix.marker.trim().is_empty() {
+        let (previous, previous_body) = split_prefix(&lines[start - 1]);
+        if is_block_boundary(previous_body, &previous) || previous.quote != prefix.quote {
+            break;
+        }
+        start -= 1;
+        if !previous.marker.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut end = row;
+    while end + 1 < lines.len() {
+        let (next, next_body) = split_prefix(&lines[end + 1]);
+        if is_block_boundary(next_body, &next) || next.quote != prefix.quote || !next.marker.trim().is_empty() {
+            break;
+        }
+        end += 1;
+    }
+    Some((start, end))
+}
+
+/// Greedy fill of `words` into lines no wider than `width`, first line after `first`, the rest after `rest`
+fn fill(words: &[&str], first: &str, rest: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = first.to_string();
+    let mut has_word = false;
+
+    for word in words {
+        let candidate = current.chars().count() + usize::from(has_word) + word.chars().count();
+        if has_word && candidate > width {
+            lines.push(std::mem::replace(&mut current, rest.to_string()));
+            has_word = false;
+        }
+        if has_word {
+            current.push(' ');
+        }
+        current.push_str(word);
+        has_word = true;
+    }
+    lines.push(current);
+    lines
+}
+
+/// Re-wrap the paragraph around `row` to `width`, keeping quote and list prefixes
+pub fn reflow_paragraph(lines: &[String], row: usize, width: usize) -> Option<(usize, usize, Vec<String>)> {
+    let (start, end) = paragraph_bounds(lines, row)?;
+    let (first_prefix, _) = split_prefix(&lines[start]);
+    let first = format!("{}{}", first_prefix.quote, first_prefix.marker);
+    let continuation = format!("{}{}", first_prefix.quote, " ".repeat(first_prefix.marker.chars().count()));
+
+    let words: Vec<&str> = lines[start..=end]
+        .iter()
+        .flat_map(|line| split_prefix(line).1.split_whitespace())
+        .collect();
+    Some((start, end, fill(&words, &first, &continuation, width)))
+}
+
+impl<'a> App<'a> {
+    pub fn reflow_current_paragraph(&mut self) {
+        let width = self.editor_options.text_width;
+        let Some(editor) = self.active_editor() else {
+            return;
+        };
+        let lines = editor.lines();
+        let Some((start, end, reflowed)) = reflow_paragraph(lines, editor.cursor().0, width) else {
+            return;
+        };
+        if reflowed.as_slice() == &lines[start..=end] {
+            return;
+        }
+
+        let new_row = start + reflowed.len() - 1;
+        let new_col = reflowed.last().map(|line| line.chars().count()).unwrap_or(0);
+        let mut new_lines = lines[..start].to_vec();
+        new_lines.extend(reflowed);
+        new_lines.extend_from_slice(&lines[end + 1..]);
+        self.apply_replacement(new_lines, (new_row, new_col));
+        if let Some(editor) = self.active_editor_mut() {
+            editor.move_cursor(CursorMove::Jump(new_row as u16, new_col as u16));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::app::App;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum SplitDirection {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum SplitSide {
+    First,
+    Second,
+}
+
+/// Two buffers shown side by side; the focused one is always `App.active_buffer`
+pub struct SplitState {
+    pub direction: SplitDirection,
+    pub other_buffer: usize,
+    pub focus: SplitSide,
+}
+
+impl SplitState {
+    /// Buffer indices for the first (left/top) and second (right/bottom) pane
+    pub fn panes(&self, active_buffer: usize) -> (usize, usize) {
+        match self.focus {
+            SplitSide::First => (active_buffer, self.other_buffer),
+            SplitSide::Second => (self.other_buffer, active_buffer),
+        }
+    }
+}
+
+impl<'a> App<'a> {
+    /// Open the note selected in the file list beside the active buffer
+    pub fn open_in_split(&mut self) {
+        let Some(path) = self
+            .file_list_state
+            .selected()
+            .and_then(|index| self.get_current_files().get(index).cloned())
+        else {
+            return;
+        };
+        if !path.is_file() || self.buffers.is_empty() {
+            self.start_editing();
+            return;
+        }
+
+        let other_buffer = self.active_buffer;
+        self.open_buffer(&path);
+        if self.active_buffer != other_buffer {
+            self.split = Some(SplitState {
+                direction: SplitDirection::Vertical,
+                other_buffer,
+                focus: SplitSide::Second,
+            });
+            self.refresh_editor_blocks();
+        }
+    }
+
+    /// Cycle vertical split -> horizontal split -> single pane
+    pub fn cycle_split(&mut self) {
+        self.split = match self.split.take() {
+            None if self.buffers.len() >= 2 => Some(SplitState {
+                direction: SplitDirection::Vertical,
+                other_buffer: (self.active_buffer + 1) % self.buffers.len(),
+                focus: SplitSide::First,
+            }),
+            None => None,
+            Some(split) if split.direction == SplitDirection::Vertical => Some(SplitState {
+                direction: SplitDirection::Horizontal,
+                ..split
+            }),
+            Some(_) => None,
+        };
+        self.refresh_editor_blocks();
+    }
+
+    pub fn switch_split_focus(&mut self) {
+        if let Some(split) = self.split.as_mut() {
+            std::mem::swap(&mut self.active_buffer, &mut split.other_buffer);
+            split.focus = match split.focus {
+                SplitSide::First => SplitSide::Second,
+                SplitSide::Second => SplitSide::First,
+            };
+        }
+        self.refresh_editor_blocks();
+    }
+
+    pub fn is_split_editing(&self) -> bool {
+        self.split.is_some() && self.editor_visible()
+    }
+
+    /// Mark the focused editor's border so it's clear which pane receives keys
+    pub fn refresh_editor_blocks(&mut self) {
+        let active = self.active_buffer;
+        let split = self.split.is_some();
+        let blocks: Vec<_> = (0..self.buffers.len())
+            .map(|index| self.editor_block(!split || index == active))
+            .collect();
+        for (buffer, block) in self.buffers.iter_mut().zip(blocks) {
+            buffer.editor.set_block(block);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use std::{fs, path::{Path, PathBuf}};
+use tui_textarea::{CursorMove, TextArea};
+use walkdir::WalkDir;
+
+use crate::app::App;
+use crate::file_ops::IgnoreList;
+use crate::modes::Mode;
+
+pub struct Task {
+    pub path: PathBuf,
+    pub line: usize,
+    pub text: String,
+    pub tags: Vec<String>,
+    pub due: Option<String>,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum TaskGrouping {
+    Note,
+    Tag,
+    DueDate,
+}
+
+/// A row in the tasks view: either a group heading or an index into `App.tasks`
+pub enum TaskRow {
+    Group(String),
+    Task(usize),
+}
+
+impl TaskGrouping {
+    pub fn next(self) -> Self {
+        match self {
+            TaskGrouping::Note => TaskGrouping::Tag,
+            TaskGrouping::Tag => TaskGrouping::DueDate,
+            TaskGrouping::DueDate => TaskGrouping::Note,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TaskGrouping::Note => "NOTE",
+            TaskGrouping::Tag => "TAG",
+            TaskGrouping::DueDate => "DUE DATE",
+        }
+    }
+}
+
+/// Byte offset of the checkbox mark in a `- [ ]` / `* [x]` / `1. [ ]` line, with whether it is checked
+pub fn checkbox_position(line: &str) -> Option<(usize, bool)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let marker_len = if trimmed.starts_with("- ") || trimmed.starts_with("* ") || trimmed.starts_with("+ ") {
+        2
+    } else {
+        let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+        let after = &trimmed[digits..];
+        if digits > 0 && (after.starts_with(". ") || after.starts_with(") ")) {
+            digits + 2
+        } else {
+            return None;
+        }
+    };
+
+    let rest = &trimmed[marker_len..];
+    let checked = match rest.get(..3) {
+        Some("[ ]") => false,
+        Some("[x]") | Some("[X]") => true,
+        _ => return None,
+    };
+    if !(rest.len() == 3 || rest[3..].starts_with(' ')) {
+        return None;
+    }
+    Some((indent + marker_len + 1, checked))
+}
+
+pub fn toggle_checkbox(line: &str) -> Option<String> {
+    let (mark, checked) = checkbox_position(line)?;
+    let replacement = if checked { " " } else { "x" };
+    Some(format!("{}{}{}", &line[..mark], replacement, &line[mark + 1..]))
+}
+
+/// `due:2026-10-20` or `📅 2026-10-20`
+fn parse_due(text: &str) -> Option<String> {
+    let date = if let Some(index) = text.find("due:") {
+        &text[index + 4..]
+    } else if let Some(index) = text.find('📅') {
+        text[index + '📅'.len_utf8()..].trim_start()
+    } else {
+        return None;
+    };
+    let date: String = date.chars().take(10).collect();
+    let bytes = date.as_bytes();
+    let is_date = bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes.iter().enumerate().all(|(i, b)| i == 4 || i == 7 || b.is_ascii_digit());
+    is_date.then_some(date)
+}
+
+fn parse_tags(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|word| word.strip_prefix('#'))
+        .map(|tag| tag.trim_end_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '_' && c != '/'))
+        .filter(|tag| !tag.is_empty() && !tag.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+pub fn parse_open_tasks(path: &Path, content: &str) -> Vec<Task> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(line_index, line)| {
+            let (mark, checked) = checkbox_position(line)?;
+            if checked {
+                return None;
+            }
+            let text = line[mark + 2..].trim().to_string();
+            Some(Task {
+                path: path.to_path_buf(),
+                line: line_index,
+                tags: parse_tags(&text),
+                due: parse_due(&text),
+                text,
+            })
+        })
+        .collect()
+}
+
+/// Every open task in the markdown files below `root`
+pub fn collect_open_tasks(root: &Path, ignore: &IgnoreList) -> Vec<Task> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !(entry.file_name().to_string_lossy().starts_with('.') || ignore.matches(entry.path())))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext == "md"))
+        .flat_map(|entry| {
+            let content = fs::read_to_string(entry.path()).unwrap_or_default();
+            parse_open_tasks(entry.path(), &content)
+        })
+        .collect()
+}
+
+/// Toggle the checkbox on `line` of the file at `path`, rewriting the file in place
+pub fn toggle_task_in_file(path: &Path, line: usize) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let Some(toggled) = lines.get(line).and_then(|text| toggle_checkbox(text)) else {
+        return false;
+    };
+    lines[line] = toggled;
+    let mut new_content = lines.join("\n");
+    if content.ends_with('\n') {
+        new_content.push('\n');
+    }
+    fs::write(path, new_content).is_ok()
+}
+
+/// Toggle the checkbox on `row` through the editor's own edits, so it stays undoable
+fn toggle_task_in_editor(editor: &mut TextArea, row: usize) {
+    let Some((mark, checked)) = editor.lines().get(row).and_then(|line| {
+        checkbox_position(line).map(|(mark, checked)| (line[..mark].chars().count(), checked))
+    }) else {
+        return;
+    };
+
+    let (cursor_row, cursor_col) = editor.cursor();
+    editor.move_cursor(CursorMove::Jump(row as u16, mark as u16));
+    editor.delete_next_char();
+    editor.insert_char(if checked { ' ' } else { 'x' });
+    editor.move_cursor(CursorMove::Jump(cursor_row as u16, cursor_col as u16));
+}
+
+impl<'a> App<'a> {
+    pub fn toggle_task_at_cursor(&mut self) {
+        if let Some(editor) = self.active_editor_mut() {
+            let row = editor.cursor().0;
+            toggle_task_in_editor(editor, row);
+        }
+    }
+
+    /// Toggle the first task at or below the top of the preview
+    pub fn toggle_task_in_preview(&mut self) {
+        let Some(path) = self.selected_file().filter(|path| path.is_file()).cloned() else {
+            return;
+        };
+        let top = self.current_preview_scroll();
+        let line = self
+            .preview_lines()
+            .iter()
+            .skip(top)
+            .position(|line| checkbox_position(line).is_some())
+            .map(|offset| top + offset);
+        if let Some(line) = line {
+            self.toggle_task(&path, line);
+        }
+    }
+
+    /// Toggle a task in the file on disk, and in its open buffer if there is one
+    fn toggle_task(&mut self, path: &Path, line: usize) {
+        let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.path == path) else {
+            toggle_task_in_file(path, line);
+            return;
+        };
+        if !buffer.is_modified() {
+            toggle_task_in_editor(&mut buffer.editor, line);
+            buffer.save();
+            return;
+        }
+        // `line` counts lines on disk, so find the same task in the edited buffer by its text;
+        // the file is toggled too, and the buffer's other edits are left for the user to save
+        let Some(text) = fs::read_to_string(path).ok().and_then(|content| content.lines().nth(line).map(String::from)) else {
+            return;
+        };
+        if !toggle_task_in_file(path, line) {
+            return;
+        }
+        let row = buffer
+            .editor
+            .lines()
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| **candidate == text)
+            .min_by_key(|(row, _)| row.abs_diff(line))
+            .map(|(row, _)| row);
+        if let Some(row) = row {
+            toggle_task_in_editor(&mut buffer.editor, row);
+        }
+        if let Ok(content) = fs::read_to_string(path) {
+            buffer.saved_lines = content.lines().map(String::from).collect();
+        }
+    }
+
+    pub fn enter_tasks_mode(&mut self) {
+        self.tasks = collect_open_tasks(&self.root, &self.ignore);
+        self.rebuild_task_rows();
+        self.task_list_state.select(self.first_task_row(0));
+        self.mode = Mode::Tasks;
+    }
+
+    pub fn exit_tasks_mode(&mut self) {
+        self.tasks.clear();
+        self.task_rows.clear();
+        self.mode = Mode::Normal;
+    }
+
+    pub fn cycle_task_grouping(&mut self) {
+        self.task_grouping = self.task_grouping.next();
+        self.rebuild_task_rows();
+        self.task_list_state.select(self.first_task_row(0));
+    }
+
+    fn rebuild_task_rows(&mut self) {
+        let mut groups: Vec<(String, usize)> = Vec::new();
+        for (index, task) in self.tasks.iter().enumerate() {
+            let keys = match self.task_grouping {
+                TaskGrouping::Note => vec![task
+                    .path
+                    .strip_prefix(&self.root)
+                    .unwrap_or(&task.path)
+                    .to_string_lossy()
+                    .into_owned()],
+                TaskGrouping::Tag if task.tags.is_empty() => vec!["Untagged".to_string()],
+                TaskGrouping::Tag => task.tags.iter().map(|tag| format!("#{}", tag)).collect(),
+                // `~` sorts after any digit, so undated tasks land at the end
+                TaskGrouping::DueDate => vec![task.due.clone().unwrap_or_else(|| "~ No due date".to_string())],
+            };
+            groups.extend(keys.into_iter().map(|key| (key, index)));
+        }
+        groups.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        self.task_rows.clear();
+        let mut current_group: Option<&str> = None;
+        for (key, index) in &groups {
+            if current_group != Some(key.as_str()) {
+                self.task_rows.push(TaskRow::Group(key.trim_start_matches("~ ").to_string()));
+                current_group = Some(key);
+            }
+            self.task_rows.push(TaskRow::Task(*index));
+        }
+    }
+
+    fn first_task_row(&self, from: usize) -> Option<usize> {
+        self.task_rows
+            .iter()
+            .enumerate()
+            .skip(from)
+            .find(|(_, row)| matches!(row, TaskRow::Task(_)))
+            .map(|(i, _)| i)
+    }
+
+    pub fn selected_task(&self) -> Option<&Task> {
+        match self.task_rows.get(self.task_list_state.selected()?)? {
+            TaskRow::Task(index) => self.tasks.get(*index),
+            TaskRow::Group(_) => None,
+        }
+    }
+
+    pub fn task_next(&mut self) {
+        let start = self.task_list_state.selected().map(|i| i + 1).unwrap_or(0);
+        let next = self.first_task_row(start).or_else(|| self.first_task_row(0));
+        self.task_list_state.select(next);
+    }
+
+    pub fn task_previous(&mut self) {
+        let start = self.task_list_state.selected().unwrap_or(0);
+        let previous = self.task_rows[..start]
+            .iter()
+            .rposition(|row| matches!(row, TaskRow::Task(_)))
+            .or_else(|| self.task_rows.iter().rposition(|row| matches!(row, TaskRow::Task(_))));
+        self.task_list_state.select(previous);
+    }
+
+    /// Mark the selected task done in its source note and drop it from the list
+    pub fn complete_selected_task(&mut self) {
+        let Some((path, line)) = self.selected_task().map(|task| (task.path.clone(), task.line)) else {
+            return;
+        };
+        self.toggle_task(&path, line);
+
+        let selected = self.task_list_state.selected().unwrap_or(0);
+        self.tasks = collect_open_tasks(&self.root, &self.ignore);
+        self.rebuild_task_rows();
+        let row = self.first_task_row(selected.saturating_sub(1)).or_else(|| self.first_task_row(0));
+        self.task_list_state.select(row);
+    }
+
+    pub fn open_selected_task(&mut self) {
+        let Some((path, line)) = self.selected_task().map(|task| (task.path.clone(), task.line)) else {
+            return;
+        };
+        self.exit_tasks_mode();
+        self.open_buffer(&path);
+        if let Some(editor) = self.active_editor_mut() {
+            editor.move_cursor(CursorMove::Jump(line as u16, 0));
+        }
+    }
+}
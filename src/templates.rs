@@ -0,0 +1,349 @@
+use chrono::{format::{Item, StrftimeItems}, Local, NaiveDateTime};
+use regex::Regex;
+use std::{collections::HashMap, fmt::Write, fs, path::{Component, Path, PathBuf}, sync::OnceLock};
+use tui_textarea::CursorMove;
+
+use crate::app::App;
+use crate::clipboard::read_clipboard_text;
+use crate::modes::Mode;
+use fuzzy_matcher::FuzzyMatcher;
+
+/// What a template is being expanded for
+pub struct TemplateContext {
+    pub title: String,
+    pub date: NaiveDateTime,
+    pub root: PathBuf,
+}
+
+/// Template-only frontmatter keys that decide where a new note is filed
+#[derive(Default)]
+pub struct TemplateSettings {
+    pub filename: Option<String>,
+    pub folder: Option<String>,
+    pub stage: Option<String>,
+    pub description: Option<String>,
+}
+
+/// A template as listed in the picker, categorised by its subfolder
+#[derive(Clone)]
+pub struct TemplateEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub category: Option<String>,
+    pub description: Option<String>,
+}
+
+impl TemplateEntry {
+    pub fn load(template_root: &Path, path: PathBuf) -> Self {
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let category = path
+            .parent()
+            .and_then(|folder| folder.strip_prefix(template_root).ok())
+            .filter(|folder| !folder.as_os_str().is_empty())
+            .map(|folder| folder.to_string_lossy().into_owned());
+        Self {
+            name: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+            category,
+            description: split_template_settings(&content).0.description,
+            path,
+        }
+    }
+
+    /// Text the picker's fuzzy filter matches against
+    pub fn search_text(&self) -> String {
+        format!(
+            "{}/{} {}",
+            self.category.as_deref().unwrap_or_default(),
+            self.name,
+            self.description.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+/// A note waiting on answers to its template's `{{prompt:...}}` placeholders
+pub struct PendingNote {
+    pub title: String,
+    pub template: String,
+    pub prompts: Vec<String>,
+    pub answers: HashMap<String, String>,
+    pub input: String,
+}
+
+impl PendingNote {
+    pub fn current_prompt(&self) -> Option<&str> {
+        self.prompts.get(self.answers.len()).map(String::as_str)
+    }
+}
+
+pub struct ExpandedTemplate {
+    pub text: String,
+    /// Row and column of the `{{cursor}}` marker
+    pub cursor: Option<(usize, usize)>,
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\{\{\s*([^{}]+?)\s*\}\}").unwrap())
+}
+
+/// Labels of the `{{prompt:...}}` placeholders, in order of first appearance
+pub fn template_prompts(content: &str) -> Vec<String> {
+    let mut prompts: Vec<String> = Vec::new();
+    for captures in placeholder_regex().captures_iter(content) {
+        if let Some(label) = captures[1].strip_prefix("prompt:") {
+            let label = label.trim().to_string();
+            if !prompts.contains(&label) {
+                prompts.push(label);
+            }
+        }
+    }
+    prompts
+}
+
+/// Format with a user-supplied strftime pattern, refusing patterns chrono can't render
+fn format_date(date: &NaiveDateTime, pattern: &str) -> Option<String> {
+    let items: Vec<Item> = StrftimeItems::new(pattern).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+    let mut formatted = String::new();
+    write!(formatted, "{}", date.format_with_items(items.into_iter())).ok()?;
+    Some(formatted)
+}
+
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn expand_placeholder(name: &str, context: &TemplateContext, answers: &HashMap<String, String>) -> Option<String> {
+    let (key, argument) = match name.split_once(':') {
+        Some((key, argument)) => (key.trim(), Some(argument.trim())),
+        None => (name, None),
+    };
+    match key {
+        "title" => Some(context.title.clone()),
+        "date" => format_date(&context.date, argument.unwrap_or("%Y-%m-%d")),
+        "time" => format_date(&context.date, argument.unwrap_or("%H:%M")),
+        "uuid" => Some(uuid::Uuid::new_v4().to_string()),
+        "root" => Some(context.root.to_string_lossy().into_owned()),
+        "clipboard" => Some(read_clipboard_text().unwrap_or_default()),
+        "slug" => expand_placeholder(argument?, context, answers).map(|value| slugify(&value)),
+        "prompt" => Some(answers.get(argument.unwrap_or_default()).cloned().unwrap_or_default()),
+        _ => None,
+    }
+}
+
+/// Expand every known placeholder; unknown ones are left untouched
+pub fn expand_template(content: &str, context: &TemplateContext, answers: &HashMap<String, String>) -> ExpandedTemplate {
+    let mut text = String::with_capacity(content.len());
+    let mut cursor_offset = None;
+    let mut last = 0;
+
+    for captures in placeholder_regex().captures_iter(content) {
+        let whole = captures.get(0).unwrap();
+        text.push_str(&content[last..whole.start()]);
+        last = whole.end();
+
+        if &captures[1] == "cursor" {
+            cursor_offset.get_or_insert(text.len());
+        } else if let Some(value) = expand_placeholder(&captures[1], context, answers) {
+            text.push_str(&value);
+        } else {
+            text.push_str(whole.as_str());
+        }
+    }
+    text.push_str(&content[last..]);
+
+    let cursor = cursor_offset.map(|offset| {
+        let before = &text[..offset];
+        let row = before.matches('\n').count();
+        let column = before.rsplit('\n').next().unwrap_or_default().chars().count();
+        (row, column)
+    });
+    ExpandedTemplate { text, cursor }
+}
+
+/// Pull the filing keys out of a template's frontmatter, leaving the rest (tags etc.) for the note
+pub fn split_template_settings(content: &str) -> (TemplateSettings, String) {
+    let mut settings = TemplateSettings::default();
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return (settings, content.to_string());
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (settings, content.to_string());
+    };
+
+    let mut kept = Vec::new();
+    for line in rest[..end].lines() {
+        let value = |value: &str| Some(value.trim().trim_matches(|c| c == '"' || c == '\'').to_string());
+        match line.split_once(':') {
+            Some(("filename", value_text)) => settings.filename = value(value_text),
+            Some(("folder", value_text)) => settings.folder = value(value_text),
+            Some(("stage", value_text)) => settings.stage = value(value_text),
+            Some(("description", value_text)) => settings.description = value(value_text),
+            _ => kept.push(line),
+        }
+    }
+
+    let body = &rest[end + 4..];
+    let note = if kept.iter().all(|line| line.trim().is_empty()) {
+        body.trim_start_matches(['\r', '\n']).to_string()
+    } else {
+        format!("---\n{}\n---{}", kept.join("\n"), body)
+    };
+    (settings, note)
+}
+
+/// `base` joined with `relative`, unless that's absolute or uses `..` and so could land outside `base`
+fn path_within(base: &Path, relative: &str) -> Option<PathBuf> {
+    Path::new(relative)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        .then(|| base.join(relative))
+}
+
+/// `path`, or `name-2.md`, `name-3.md`… if it's taken
+fn unique_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{}-{}{}", stem, n, extension)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(path)
+}
+
+impl<'a> App<'a> {
+    pub fn update_filtered_templates(&mut self) {
+        if self.template_filter.is_empty() {
+            self.filtered_templates = self.template_files.clone();
+        } else {
+            let mut scored: Vec<(TemplateEntry, i64)> = self
+                .template_files
+                .iter()
+                .filter_map(|template| {
+                    self.fuzzy_matcher
+                        .fuzzy_match(&template.search_text(), &self.template_filter)
+                        .map(|score| (template.clone(), score))
+                })
+                .collect();
+            scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+            self.filtered_templates = scored.into_iter().map(|(template, _)| template).collect();
+        }
+
+        let selection = if self.filtered_templates.is_empty() { None } else { Some(0) };
+        self.template_list_state.select(selection);
+    }
+
+    pub fn template_filter_input(&mut self, c: char) {
+        self.template_filter.push(c);
+        self.update_filtered_templates();
+    }
+
+    pub fn template_filter_backspace(&mut self) {
+        self.template_filter.pop();
+        self.update_filtered_templates();
+    }
+
+    pub fn selected_template(&self) -> Option<&TemplateEntry> {
+        self.filtered_templates.get(self.template_list_state.selected()?)
+    }
+
+    /// Ask for any prompt answers the template needs, then write the note
+    pub fn create_from_template(&mut self, title: String, template: String) {
+        let prompts = template_prompts(&template);
+        let pending = PendingNote {
+            title,
+            template,
+            prompts,
+            answers: HashMap::new(),
+            input: String::new(),
+        };
+        if pending.current_prompt().is_some() {
+            self.pending_note = Some(pending);
+            self.mode = Mode::TemplatePrompt;
+        } else {
+            self.write_templated_note(pending);
+        }
+    }
+
+    pub fn submit_template_prompt(&mut self) {
+        let Some(mut pending) = self.pending_note.take() else {
+            return;
+        };
+        if let Some(prompt) = pending.current_prompt().map(String::from) {
+            let answer = std::mem::take(&mut pending.input);
+            pending.answers.insert(prompt, answer);
+        }
+        if pending.current_prompt().is_some() {
+            self.pending_note = Some(pending);
+        } else {
+            self.write_templated_note(pending);
+        }
+    }
+
+    pub fn cancel_template_prompt(&mut self) {
+        self.pending_note = None;
+        self.mode = Mode::Normal;
+    }
+
+    /// Where a templated note goes: the stage's folder (or the current directory), then any subfolder;
+    /// a subfolder or filename that would leave that folder is dropped
+    fn templated_note_path(&self, settings: &TemplateSettings, context: &TemplateContext, answers: &HashMap<String, String>) -> PathBuf {
+        let base = settings
+            .stage
+            .as_ref()
+            .and_then(|stage| self.stage_by_name(stage))
+            .map(|stage| stage.folder.clone())
+            .unwrap_or_else(|| self.root.clone());
+        let folder = settings
+            .folder
+            .as_ref()
+            .and_then(|subfolder| path_within(&base, &expand_template(subfolder, context, answers).text))
+            .unwrap_or_else(|| base.clone());
+
+        let filename = match &settings.filename {
+            Some(pattern) => expand_template(pattern, context, answers).text,
+            None => context.title.clone(),
+        };
+        let filename = if filename.ends_with(".md") { filename } else { format!("{}.md", filename) };
+        let path = path_within(&folder, &filename)
+            .filter(|path| path.starts_with(&base))
+            .unwrap_or_else(|| folder.join(Path::new(&filename).file_name().unwrap_or("untitled.md".as_ref())));
+        unique_path(path)
+    }
+
+    fn write_templated_note(&mut self, pending: PendingNote) {
+        let context = TemplateContext {
+            title: pending.title,
+            date: Local::now().naive_local(),
+            root: self.root.clone(),
+        };
+        let (settings, template) = split_template_settings(&pending.template);
+        let path = self.templated_note_path(&settings, &context, &pending.answers);
+        let expanded = expand_template(&template, &context, &pending.answers);
+        if let Some(folder) = path.parent() {
+            fs::create_dir_all(folder).ok();
+        }
+        fs::write(&path, expanded.text).ok();
+
+        self.load_files();
+        if let Some(index) = self.files.iter().position(|file| file == &path) {
+            self.file_list_state.select(Some(index));
+        }
+        self.open_buffer(&path);
+        if let (Some((row, column)), Some(editor)) = (expanded.cursor, self.active_editor_mut()) {
+            editor.move_cursor(CursorMove::Jump(row as u16, column as u16));
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::ops::Range;
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+
+use crate::buffers::Buffer;
+use crate::editor_options::{EditorOptions, LineNumbers};
+use crate::ui::themes::ColorScheme;
+
+/// Keep `cursor` inside a window of `len` cells starting at `prev_top`
+fn next_scroll_top(prev_top: usize, cursor: usize, len: usize) -> usize {
+    if cursor < prev_top {
+        cursor
+    } else if len > 0 && prev_top + len <= cursor {
+        cursor + 1 - len
+    } else {
+        prev_top
+    }
+}
+
+/// Char ranges a line occupies on screen, breaking at the last space before `width` when possible
+fn wrap_segments(line: &str, width: Option<usize>) -> Vec<Range<usize>> {
+    let chars: Vec<char> = line.chars().collect();
+    let Some(width) = width.filter(|width| chars.len() > *width) else {
+        return std::iter::once(0..chars.len()).collect();
+    };
+
+    let mut segments = Vec::new();
+    let mut start = 0;
+    while chars.len() - start > width {
+        let limit = start + width;
+        let end = chars[start..limit]
+            .iter()
+            .rposition(|c| c.is_whitespace())
+            .map(|space| start + space + 1)
+            .filter(|end| *end > start)
+            .unwrap_or(limit);
+        segments.push(start..end);
+        start = end;
+    }
+    segments.push(start..chars.len());
+    segments
+}
+
+/// Screen row of `col` within the wrapped segments of a line
+fn segment_of(segments: &[Range<usize>], col: usize) -> usize {
+    segments
+        .iter()
+        .position(|segment| col < segment.end)
+        .unwrap_or(segments.len() - 1)
+}
+
+fn digits(n: usize) -> usize {
+    n.max(1).to_string().len()
+}
+
+/// Render a buffer's text with markdown highlighting, search matches, selection and cursor
+pub fn render_editor(
+    frame: &mut Frame,
+    buffer: &mut Buffer,
+    area: Rect,
+    color_scheme: ColorScheme,
+    options: &EditorOptions,
+    focused: bool,
+) {
+    let inner = match buffer.editor.block() {
+        Some(block) => {
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            inner
+        }
+        None => area,
+    };
+
+    let line_count = buffer.editor.lines().len();
+    let gutter_width = match options.line_numbers {
+        LineNumbers::Off => 0,
+        _ => (digits(line_count).max(3) + 1).min(inner.width as usize) as u16,
+    };
+    let [gutter_area, text_area] = Layout::horizontal([Constraint::Length(gutter_width), Constraint::Min(0)]).areas(inner);
+
+    let (cursor_row, cursor_col) = buffer.editor.cursor();
+    let height = text_area.height as usize;
+    let wrap_width = options.wrap_width(text_area.width as usize);
+    buffer.view_height = text_area.height;
+
+    if wrap_width.is_some() {
+        buffer.scroll_left = 0;
+        buffer.scroll_top = buffer.scroll_top.min(cursor_row);
+        // Walk back from the cursor's wrapped row, taking in lines above it while they fit on screen
+        let lines = buffer.editor.lines();
+        let mut rows = segment_of(&wrap_segments(&lines[cursor_row], wrap_width), cursor_col) + 1;
+        let mut top = cursor_row;
+        while top > buffer.scroll_top {
+            let line_rows = wrap_segments(&lines[top - 1], wrap_width).len();
+            if rows + line_rows > height {
+                break;
+            }
+            rows += line_rows;
+            top -= 1;
+        }
+        buffer.scroll_top = top;
+    } else {
+        buffer.scroll_top = next_scroll_top(buffer.scroll_top, cursor_row, height);
+        buffer.scroll_left = next_scroll_top(buffer.scroll_left, cursor_col, text_area.width as usize);
+    }
+
+    // `TextArea::selection_style` takes `&mut self`, so read it before borrowing the lines
+    let selection_style = buffer.editor.selection_style();
+    let editor = &buffer.editor;
+    let top = buffer.scroll_top;
+    let bottom = (top + height).min(line_count);
+    let highlighted = buffer.highlight.update(editor.lines(), bottom);
+
+    let number_style = Style::default().fg(Color::DarkGray);
+    let mut text: Vec<Line> = Vec::with_capacity(height);
+    let mut gutter: Vec<Line> = Vec::with_capacity(height);
+
+    for (row, highlighted_line) in highlighted.iter().enumerate().skip(top) {
+        if text.len() >= height {
+            break;
+        }
+        let line = &editor.lines()[row];
+        let offsets: Vec<usize> = line.char_indices().map(|(i, _)| i).collect();
+        let mut styles = vec![Style::default(); offsets.len() + 1];
+        let mut patch = |range: Range<usize>, style: Style| {
+            for (i, offset) in offsets.iter().enumerate() {
+                if range.contains(offset) {
+                    styles[i] = styles[i].patch(style);
+                }
+            }
+        };
+
+        for (range, token) in &highlighted_line.tokens {
+            patch(range.clone(), color_scheme.highlight_style(*token));
+        }
+        if let Some(pattern) = editor.search_pattern() {
+            for found in pattern.find_iter(line) {
+                patch(found.range(), editor.search_style());
+            }
+        }
+        if let Some(((start_row, start_col), (end_row, end_col))) = editor.selection_range() {
+            if (start_row..=end_row).contains(&row) {
+                let from = if row == start_row { start_col } else { 0 };
+                let to = if row == end_row { end_col } else { offsets.len() + 1 };
+                for style in styles.iter_mut().take(to).skip(from) {
+                    *style = style.patch(selection_style);
+                }
+            }
+        }
+        let is_cursor_row = focused && row == cursor_row;
+        if is_cursor_row {
+            for style in styles.iter_mut() {
+                *style = style.patch(editor.cursor_line_style());
+            }
+            if let Some(style) = styles.get_mut(cursor_col) {
+                *style = style.patch(editor.cursor_style());
+            }
+        }
+
+        let number = match options.line_numbers {
+            LineNumbers::Relative if row != cursor_row => row.abs_diff(cursor_row),
+            _ => row + 1,
+        };
+        let segments = wrap_segments(line, wrap_width);
+        let last_segment = segments.len() - 1;
+        for (index, segment) in segments.into_iter().enumerate() {
+            let label = if index == 0 { number.to_string() } else { String::new() };
+            gutter.push(Line::styled(format!("{:>width$} ", label, width = gutter_width.saturating_sub(1) as usize), number_style));
+            let segment_text: String = line.chars().skip(segment.start).take(segment.len()).collect();
+            let cursor_at_end = is_cursor_row && index == last_segment && cursor_col >= offsets.len();
+            text.push(styled_line(&segment_text, &styles[segment.start..], cursor_at_end));
+        }
+    }
+    text.truncate(height);
+    gutter.truncate(height);
+
+    let paragraph = Paragraph::new(text)
+        .style(editor.style())
+        .scroll((0, buffer.scroll_left as u16));
+    frame.render_widget(paragraph, text_area);
+    if gutter_width > 0 {
+        frame.render_widget(Paragraph::new(gutter).style(editor.style()), gutter_area);
+    }
+
+    if options.show_ruler {
+        let column = options.text_width.checked_sub(buffer.scroll_left).filter(|column| *column < text_area.width as usize);
+        if let Some(column) = column {
+            let x = text_area.x + column as u16;
+            let ruler_style = Style::default().bg(Color::DarkGray);
+            let frame_buffer = frame.buffer_mut();
+            for y in text_area.top()..text_area.bottom() {
+                if let Some(cell) = frame_buffer.cell_mut((x, y)) {
+                    cell.set_style(ruler_style);
+                }
+            }
+        }
+    }
+}
+
+/// Group consecutive characters that share a style into spans, with a trailing cell for an end-of-line cursor
+fn styled_line(line: &str, styles: &[Style], cursor_at_end: bool) -> Line<'static> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut current_style = styles.first().copied().unwrap_or_default();
+
+    for (c, style) in line.chars().zip(styles) {
+        if *style != current_style && !current.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut current), current_style));
+        }
+        current_style = *style;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(current, current_style));
+    }
+
+    if cursor_at_end {
+        let end = line.chars().count().min(styles.len().saturating_sub(1));
+        spans.push(Span::styled(" ", styles.get(end).copied().unwrap_or_default()));
+    }
+    Line::from(spans)
+}
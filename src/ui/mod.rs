@@ -1,5 +1,6 @@
 pub mod themes;
 pub mod render;
 pub mod components;
+pub mod editor_view;
 
 pub use render::ui;
\ No newline at end of file
@@ -1,11 +1,26 @@
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, Borders, BorderType, Clear, List, ListItem, Paragraph};
+use ratatui::widgets::{Block, Borders, BorderType, Clear, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Tabs, Wrap};
+use chrono::{DateTime, Datelike, Local};
+use regex::RegexBuilder;
 use std::{fs, time::SystemTime};
 
+use crate::actions::Action;
 use crate::app::App;
+use crate::batch::BatchAction;
+use crate::config;
+use crate::file_sort::format_size;
+use crate::bulk_rename::RenameField;
+use crate::find::FindField;
+use crate::history::{average_stage_times, format_duration};
+use crate::journal::grid_start;
+use crate::split::SplitDirection;
+use crate::tasks::TaskRow;
+use crate::vault::Setting;
+use crate::workflow::SettingsSection;
 use crate::modes::Mode;
 use crate::ui::themes::ColorScheme;
 use crate::ui::components::centered_rect;
+use crate::ui::editor_view::render_editor;
 use crate::constants::WEYLAND_YUTANI_LOGO;
 
 pub fn ui(frame: &mut Frame, app: &mut App) {
@@ -29,15 +44,39 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
 
     render_header(frame, app, outer_layout[0]);
     render_corporate_text(frame, app, outer_layout[1]);
+
+    // Draw the screen the command palette was opened from underneath it
+    let mode = app.mode;
+    if let Some(palette) = app.palette.as_ref() {
+        app.mode = palette.return_mode;
+    }
     
+    // Collapse the file list while two editors share the screen
+    let list_width = match (app.is_split_editing(), app.outline_visible()) {
+        (true, true) => 20,
+        (true, false) => 0,
+        _ => 30,
+    };
     let main_layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .constraints([Constraint::Percentage(list_width), Constraint::Percentage(100 - list_width)])
         .split(outer_layout[2]);
 
-    render_left_pane(frame, app, main_layout[0], current_time);
-    render_right_pane(frame, app, main_layout[1]);
+    if app.mode == Mode::Help {
+        render_help(frame, app, outer_layout[2]);
+    } else if app.board.is_some() {
+        render_board(frame, app, outer_layout[2]);
+    } else if app.bulk_rename.is_some() {
+        render_bulk_rename(frame, app, outer_layout[2]);
+    } else {
+        render_left_pane(frame, app, main_layout[0], current_time);
+        render_right_pane(frame, app, main_layout[1]);
+    }
     render_popups(frame, app, current_time);
+    if app.mode != mode {
+        app.mode = mode;
+        render_popups(frame, app, current_time);
+    }
     render_status_bar(frame, app, outer_layout[3], current_time);
 }
 
@@ -69,9 +108,184 @@ fn render_corporate_text(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(text_widget, area);
 }
 
+fn render_board(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(board) = app.board.as_ref() else {
+        return;
+    };
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, board.columns.len() as u32); board.columns.len()])
+        .split(layout[0]);
+
+    for (index, (column, column_area)) in board.columns.iter().zip(columns.iter()).enumerate() {
+        let focused = index == board.column;
+        let count = match column.wip_limit {
+            Some(limit) => format!("{}/{}", column.cards.len(), limit),
+            None => column.cards.len().to_string(),
+        };
+        let title_style = if column.over_limit() {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(column.color).add_modifier(Modifier::BOLD)
+        };
+        let block = Block::default()
+            .title(Span::styled(format!(" {} {} ({}) ", column.icon, column.title.to_uppercase(), count), title_style))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(if focused { column.color } else { Color::DarkGray }))
+            .border_type(if focused { BorderType::Double } else { BorderType::Plain });
+
+        let items: Vec<ListItem> = column.cards.iter().map(|card| {
+            ListItem::new(format!("▪ {}", card.file_stem().unwrap_or_default().to_string_lossy()))
+                .style(Style::default().fg(column.color))
+        }).collect();
+        let mut state = ListState::default();
+        if focused && !column.cards.is_empty() {
+            state.select(Some(column.selected));
+        }
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(column.color).fg(Color::Black).add_modifier(Modifier::BOLD))
+            .highlight_symbol("► ");
+        frame.render_stateful_widget(list, *column_area, &mut state);
+    }
+
+    if let Some(notice) = &board.notice {
+        frame.render_widget(Paragraph::new(notice.as_str()).style(Style::default().fg(Color::Red)), layout[1]);
+    }
+}
+
+fn render_help(frame: &mut Frame, app: &App, area: Rect) {
+    let primary = Style::default().fg(app.color_scheme.primary_color());
+    let heading = Style::default().fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD);
+    let mut lines = config_error_lines(app);
+    for mode in Mode::ALL {
+        let actions = app.mode_actions(mode, false);
+        if actions.is_empty() {
+            continue;
+        }
+        lines.push(Line::styled(format!("{} [keys.{}]", mode.to_string(), mode.id()), heading));
+        for (action, keys) in actions {
+            lines.push(Line::styled(format!("  {:<34} {:<26} {}", action.title(), keys.join(" / "), action.id()), primary));
+        }
+    }
+    let block = Block::default()
+        .title(" ■■■ KEY BINDINGS ■■■ ")
+        .borders(Borders::ALL)
+        .border_style(primary)
+        .border_type(BorderType::Double);
+    frame.render_widget(Paragraph::new(lines).block(block).scroll((app.help_scroll, 0)), area);
+}
+
+/// Each config problem, flagged on its first line; TOML syntax errors span several
+fn config_error_lines(app: &App) -> Vec<Line<'static>> {
+    let style = Style::default().fg(Color::Red);
+    app.config_errors
+        .iter()
+        .flat_map(|error| {
+            error.lines().enumerate().map(move |(index, line)| {
+                let marker = if index == 0 { "⚠ " } else { "  " };
+                Line::styled(format!("{}{}", marker, line), style)
+            })
+        })
+        .collect()
+}
+
+fn render_config_errors_popup(frame: &mut Frame, app: &App) {
+    let mut lines = config_error_lines(app);
+    lines.push(Line::default());
+    let path = config::config_path().map(|path| path.display().to_string()).unwrap_or_default();
+    lines.push(Line::styled(
+        format!("Defaults are in use for these settings until {} is fixed.", path),
+        Style::default().fg(app.color_scheme.primary_color()),
+    ));
+    let height = (lines.len() as u16 + 2).clamp(5, 24);
+    let area = centered_rect(80, height, frame.area());
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(" ■■■ CONFIG ERRORS ■■■ ")
+            .border_style(Style::default().fg(app.color_scheme.primary_color()))
+            .border_type(BorderType::Double));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+fn render_bulk_rename(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(state) = app.bulk_rename.as_ref() else {
+        return;
+    };
+    let primary = Style::default().fg(app.color_scheme.primary_color());
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(7), Constraint::Min(0)])
+        .split(area);
+
+    let field = |label: &str, value: &str, active: bool| {
+        let marker = if active { "►" } else { " " };
+        let cursor = if active { "█" } else { "" };
+        let style = if active { primary.add_modifier(Modifier::BOLD) } else { primary };
+        Line::styled(format!("{} {:<9} {}{}", marker, label, value, cursor), style)
+    };
+    let mut form = vec![
+        field("Find:", &state.find, state.field == RenameField::Find),
+        field("Replace:", &state.replace, state.field == RenameField::Replace),
+        Line::styled(
+            format!(
+                "  Case: {}   Date prefix: {}   Numbering: {}",
+                state.case.name(),
+                state.date_prefix.name(),
+                if state.numbering { "on" } else { "off" }
+            ),
+            Style::default().fg(app.color_scheme.secondary_color()),
+        ),
+    ];
+    if let Some(error) = &state.error {
+        form.push(Line::styled(format!("  Invalid regex: {}", error), Style::default().fg(Color::Red)));
+    } else if state.has_collisions() {
+        form.push(Line::styled("  Name collisions flagged below; fix them before applying", Style::default().fg(Color::Red)));
+    }
+    let form_block = Block::default()
+        .title(format!(" ■■■ BULK RENAME: {} FILES ■■■ ", state.files.len()))
+        .borders(Borders::ALL)
+        .border_style(primary)
+        .border_type(BorderType::Double);
+    frame.render_widget(Paragraph::new(form).block(form_block), layout[0]);
+
+    let name_width = (layout[1].width.saturating_sub(8) / 2) as usize;
+    let items: Vec<ListItem> = state.preview.iter().map(|row| {
+        let from = row.from.file_name().unwrap_or_default().to_string_lossy();
+        let to = row.to.file_name().unwrap_or_default().to_string_lossy();
+        let style = if row.collision {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else if row.from == row.to {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            primary
+        };
+        let flag = if row.collision { " ⚠" } else { "" };
+        ListItem::new(format!("{:<width$} → {}{}", from, to, flag, width = name_width)).style(style)
+    }).collect();
+    let preview_block = Block::default()
+        .title(" ■■■ PREVIEW: OLD → NEW ■■■ ")
+        .borders(Borders::ALL)
+        .border_style(primary)
+        .border_type(BorderType::Double);
+    frame.render_widget(List::new(items).block(preview_block), layout[1]);
+}
+
 fn render_left_pane(frame: &mut Frame, app: &mut App, area: Rect, current_time: u64) {
     let block_style = Style::default().fg(app.color_scheme.primary_color());
 
+    if app.outline_visible() {
+        render_outline(frame, app, area, block_style);
+        return;
+    }
+
     match app.mode {
         Mode::ChangingDirectory | Mode::SelectingTemplateFolder => {
             render_browser_mode(frame, app, area, block_style);
@@ -79,8 +293,18 @@ fn render_left_pane(frame: &mut Frame, app: &mut App, area: Rect, current_time:
         Mode::SelectingTemplate => {
             render_template_selection(frame, app, area, block_style);
         }
-        Mode::Settings => {
-            render_settings_mode(frame, app, area);
+        Mode::Settings | Mode::EditingStage => {
+            if app.settings_section == SettingsSection::Pipeline {
+                render_pipeline_editor(frame, app, area, block_style);
+            } else {
+                render_settings_mode(frame, app, area);
+            }
+        }
+        Mode::Tasks => {
+            render_task_list(frame, app, area, block_style);
+        }
+        Mode::Timeline => {
+            render_timeline_list(frame, app, area, block_style);
         }
         _ => {
             render_file_list_mode(frame, app, area, block_style, current_time);
@@ -120,13 +344,33 @@ fn render_browser_mode(frame: &mut Frame, app: &mut App, area: Rect, block_style
 }
 
 fn render_template_selection(frame: &mut Frame, app: &mut App, area: Rect, block_style: Style) {
-    let items: Vec<ListItem> = app.template_files.iter().map(|path| {
-        ListItem::new(path.file_name().unwrap_or_default().to_string_lossy().to_string())
-            .style(Style::default().fg(app.color_scheme.primary_color()))
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let filter_widget = Paragraph::new(format!("{}█", app.template_filter))
+        .style(Style::default().fg(app.color_scheme.primary_color()).bg(Color::Black))
+        .block(Block::default().borders(Borders::ALL).border_style(block_style).title(" ■■■ FILTER TEMPLATES ■■■ ").border_type(BorderType::Double));
+    frame.render_widget(filter_widget, layout[0]);
+
+    let items: Vec<ListItem> = app.filtered_templates.iter().map(|template| {
+        let mut spans = Vec::new();
+        if let Some(category) = &template.category {
+            spans.push(Span::styled(
+                format!("{} › ", category),
+                Style::default().fg(app.color_scheme.secondary_color()),
+            ));
+        }
+        spans.push(Span::styled(template.name.clone(), Style::default().fg(app.color_scheme.primary_color())));
+        if let Some(description) = &template.description {
+            spans.push(Span::styled(format!(" — {}", description), Style::default().fg(Color::DarkGray)));
+        }
+        ListItem::new(Line::from(spans))
     }).collect();
 
     let block = Block::default()
-        .title(" Select Template ")
+        .title(format!(" Select Template ({}/{}) ", app.filtered_templates.len(), app.template_files.len()))
         .borders(Borders::ALL)
         .border_style(block_style)
         .border_type(BorderType::Double);
@@ -140,7 +384,208 @@ fn render_template_selection(frame: &mut Frame, app: &mut App, area: Rect, block
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol(" > ");
-    frame.render_stateful_widget(list, area, &mut app.template_list_state);
+    frame.render_stateful_widget(list, layout[1], &mut app.template_list_state);
+}
+
+fn render_template_preview(frame: &mut Frame, app: &App, area: Rect, block_style: Style) {
+    let (title, content) = match app.selected_template() {
+        Some(template) => (
+            format!(" ■■■ TEMPLATE: {} ■■■ ", template.name.to_uppercase()),
+            fs::read_to_string(&template.path).unwrap_or_default(),
+        ),
+        None => (" ■■■ TEMPLATE ■■■ ".to_string(), "No matching templates".to_string()),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+    frame.render_widget(Paragraph::new(content).style(block_style).block(block), area);
+}
+
+fn render_outline(frame: &mut Frame, app: &mut App, area: Rect, block_style: Style) {
+    let current = app.current_section();
+    let items: Vec<ListItem> = app.outline.iter().enumerate().map(|(i, heading)| {
+        let indent = "  ".repeat(heading.level.saturating_sub(1));
+        let marker = if Some(i) == current { "●" } else { "■" };
+        let style = if heading.level == 1 {
+            Style::default().fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.color_scheme.primary_color())
+        };
+        ListItem::new(format!("{}{} {}", indent, marker, heading.title)).style(style)
+    }).collect();
+
+    let title = if app.mode == Mode::Outline {
+        " ■■■ DOCUMENT OUTLINE [SELECT] ■■■ "
+    } else {
+        " ■■■ DOCUMENT OUTLINE ■■■ "
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.color_scheme.primary_color())
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, area, &mut app.outline_state);
+}
+
+fn render_task_list(frame: &mut Frame, app: &mut App, area: Rect, block_style: Style) {
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let items: Vec<ListItem> = app.task_rows.iter().map(|row| match row {
+        TaskRow::Group(name) => ListItem::new(format!("■ {}", name)).style(
+            Style::default().fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD),
+        ),
+        TaskRow::Task(index) => {
+            let task = &app.tasks[*index];
+            let overdue = task.due.as_deref().is_some_and(|due| due < today.as_str());
+            let style = if overdue {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(app.color_scheme.primary_color())
+            };
+            ListItem::new(format!("  [ ] {}", task.text)).style(style)
+        }
+    }).collect();
+
+    let title = format!(" ■■■ OPEN TASKS: {} [BY {}] ■■■ ", app.tasks.len(), app.task_grouping.name());
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.color_scheme.primary_color())
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, area, &mut app.task_list_state);
+}
+
+fn render_timeline_list(frame: &mut Frame, app: &mut App, area: Rect, block_style: Style) {
+    let Some(timeline) = app.timeline.as_mut() else {
+        return;
+    };
+    let items: Vec<ListItem> = timeline.journeys.iter().map(|journey| {
+        let stage = journey.transitions.last().map(|transition| transition.to.as_str()).unwrap_or_default();
+        ListItem::new(format!("{} [{}]", journey.note, stage))
+            .style(Style::default().fg(app.color_scheme.primary_color()))
+    }).collect();
+
+    let block = Block::default()
+        .title(format!(" ■■■ WORKFLOW HISTORY ({}) ■■■ ", timeline.journeys.len()))
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.color_scheme.primary_color())
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, area, &mut timeline.list_state);
+}
+
+/// The selected note's journey through the pipeline, or the per-stage averages report
+fn render_timeline_detail(frame: &mut Frame, app: &App, area: Rect, block_style: Style) {
+    let Some(timeline) = app.timeline.as_ref() else {
+        return;
+    };
+    let stage_style = |name: &str| {
+        let color = app.stage_by_name(name).map(|stage| app.stage_color(stage)).unwrap_or(app.color_scheme.primary_color());
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    };
+
+    let (title, lines) = if timeline.show_report {
+        let mut averages = average_stage_times(&timeline.journeys);
+        averages.sort_by_key(|(name, _, _)| app.stages.iter().position(|stage| stage.name == *name).unwrap_or(usize::MAX));
+        let mut lines = vec![Line::default()];
+        lines.extend(averages.into_iter().map(|(name, average, count)| Line::from(vec![
+            Span::styled(format!("{:<20}", name), stage_style(&name)),
+            Span::styled(format!("{:>10}  over {} stay(s)", format_duration(average), count), block_style),
+        ])));
+        if lines.len() == 1 {
+            lines.push(Line::styled("Not enough history yet: a stage's time is known once a note leaves it", block_style));
+        }
+        (" ■■■ AVERAGE TIME PER STAGE ■■■ ".to_string(), lines)
+    } else if let Some(journey) = timeline.list_state.selected().and_then(|index| timeline.journeys.get(index)) {
+        let mut lines = vec![Line::default()];
+        for (index, transition) in journey.transitions.iter().enumerate() {
+            let stayed = journey.transitions.get(index + 1)
+                .map(|next| format!(" (stayed {})", format_duration(next.at - transition.at)))
+                .unwrap_or_else(|| " (current)".to_string());
+            lines.push(Line::from(vec![
+                Span::styled(format!("{}  ", transition.at.format("%Y-%m-%d %H:%M")), block_style),
+                Span::styled(transition.from.clone(), stage_style(&transition.from)),
+                Span::styled(" → ", block_style),
+                Span::styled(transition.to.clone(), stage_style(&transition.to)),
+                Span::styled(stayed, Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+        (format!(" ■■■ JOURNEY: {} ■■■ ", journey.note), lines)
+    } else {
+        (" ■■■ JOURNEY ■■■ ".to_string(), vec![Line::styled("No stage changes recorded yet", block_style)])
+    };
+
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// The selected task's note, scrolled so the task sits near the top
+fn render_task_context(frame: &mut Frame, app: &App, area: Rect, block_style: Style) {
+    let block = Block::default()
+        .title(" ■■■ TASK SOURCE ■■■ ")
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+
+    let Some(task) = app.selected_task() else {
+        let empty = Paragraph::new("No open tasks").style(block_style).block(block);
+        frame.render_widget(empty, area);
+        return;
+    };
+
+    let content = fs::read_to_string(&task.path).unwrap_or_default();
+    let first = task.line.saturating_sub(3);
+    let mut lines = vec![
+        Line::styled(
+            task.path.strip_prefix(&app.root).unwrap_or(&task.path).to_string_lossy().into_owned(),
+            Style::default().fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD),
+        ),
+        Line::default(),
+    ];
+    lines.extend(content.lines().enumerate().skip(first).map(|(index, text)| {
+        let style = if index == task.line {
+            Style::default().fg(Color::Black).bg(app.color_scheme.secondary_color())
+        } else {
+            block_style
+        };
+        Line::styled(format!("{:>4} {}", index + 1, text), style)
+    }));
+
+    frame.render_widget(Paragraph::new(lines).block(block), area);
 }
 
 fn render_settings_mode(frame: &mut Frame, app: &mut App, area: Rect) {
@@ -158,7 +603,7 @@ fn render_settings_mode(frame: &mut Frame, app: &mut App, area: Rect) {
         .collect();
 
     let settings_block = Block::default()
-        .title(" ■■■ WEYLAND-YUTANI COLOR SCHEME SETTINGS ■■■ ")
+        .title(format!(" ■■■ WEYLAND-YUTANI COLOR SCHEME SETTINGS ({}) ■■■ ", app.layer_of(Setting::Theme).label().to_uppercase()))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(app.color_scheme.primary_color()))
         .border_type(BorderType::Double);
@@ -175,6 +620,9 @@ fn render_settings_mode(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_stateful_widget(list, area, &mut app.settings_selection_state);
 }
 
+/// Pinned and recent entries listed above the file list
+const BOOKMARKS_SHOWN: usize = 6;
+
 fn render_file_list_mode(frame: &mut Frame, app: &mut App, area: Rect, block_style: Style, current_time: u64) {
     let left_pane_area = if app.mode == Mode::Search {
         let search_layout = Layout::default()
@@ -202,10 +650,39 @@ fn render_file_list_mode(frame: &mut Frame, app: &mut App, area: Rect, block_sty
         area
     };
 
+    let bookmarks = if app.mode == Mode::Search { Vec::new() } else { app.bookmarks.entries() };
+    let left_pane_area = if bookmarks.is_empty() {
+        left_pane_area
+    } else {
+        let shown = bookmarks.len().min(BOOKMARKS_SHOWN);
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(shown as u16 + 2), Constraint::Min(0)])
+            .split(left_pane_area);
+        let lines: Vec<Line> = bookmarks.iter().take(shown).map(|entry| {
+            let icon = if entry.pinned { "★" } else { "↺" };
+            let suffix = if entry.path.is_dir() { "/" } else { "" };
+            let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+            let color = if entry.pinned { app.color_scheme.secondary_color() } else { app.color_scheme.primary_color() };
+            Line::styled(format!(" {} {}{}", icon, name, suffix), Style::default().fg(color))
+        }).collect();
+        let block = Block::default()
+            .title(format!(
+                " ■■■ PINNED & RECENT{} ■■■ ",
+                app.keymap.key_hint(Mode::Normal, Action::OpenSwitcher, false).map(|keys| format!(" ({})", keys)).unwrap_or_default()
+            ))
+            .borders(Borders::ALL)
+            .border_style(block_style)
+            .border_type(BorderType::Double);
+        frame.render_widget(Paragraph::new(lines).block(block), layout[0]);
+        layout[1]
+    };
+
+    let marked = if app.marked.is_empty() { String::new() } else { format!("[{} MARKED] ", app.marked.len()) };
     let title = if app.mode == Mode::Search {
-        format!(" ■■■ SEARCH RESULTS ({}/{}) ■■■ ", app.filtered_files.len(), app.files.len())
+        format!(" ■■■ SEARCH RESULTS ({}/{}) {}■■■ ", app.filtered_files.len(), app.files.len(), marked)
     } else {
-        format!(" ■■■ MU-TH-UR 6000 FILE ARCHIVE ({}) ■■■ ", app.root.to_string_lossy())
+        format!(" ■■■ MU-TH-UR 6000 FILE ARCHIVE ({}) [{}] {}■■■ ", app.root.to_string_lossy(), app.current_sort().label(), marked)
     };
 
     let file_list_block = Block::default()
@@ -215,6 +692,8 @@ fn render_file_list_mode(frame: &mut Frame, app: &mut App, area: Rect, block_sty
         .border_type(BorderType::Double);
 
     let current_files = app.get_current_files();
+    // Name column width in the detail view: pane width minus borders, highlight symbol, prefix, size and date
+    let name_width = (left_pane_area.width as usize).saturating_sub(2 + 2 + 13 + 10 + 18).max(8);
     let items: Vec<ListItem> = current_files
         .iter()
         .enumerate()
@@ -225,7 +704,25 @@ fn render_file_list_mode(frame: &mut Frame, app: &mut App, area: Rect, block_sty
             } else { 
                 ("■ [FILE]", Style::default().fg(app.color_scheme.primary_color())) 
             };
-            ListItem::new(format!("{:02} {} {}", i + 1, prefix, filename)).style(style)
+            let (mark, style) = if app.marked.contains(path) {
+                ("●", style.fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD))
+            } else {
+                (" ", style)
+            };
+            if app.file_list_options.show_details {
+                let metadata = fs::metadata(path).ok();
+                let modified = metadata
+                    .as_ref()
+                    .and_then(|meta| meta.modified().ok())
+                    .map(|time| DateTime::<Local>::from(time).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                let size = if path.is_dir() { String::new() } else { format_size(metadata.map(|meta| meta.len()).unwrap_or(0)) };
+                let name: String = filename.chars().take(name_width).collect();
+                let line = format!("{}{:02} {} {:<name_width$} {:>8}  {}", mark, i + 1, prefix, name, size, modified);
+                ListItem::new(line).style(style)
+            } else {
+                ListItem::new(format!("{}{:02} {} {}", mark, i + 1, prefix, filename)).style(style)
+            }
         })
         .collect();
 
@@ -245,15 +742,178 @@ fn render_file_list_mode(frame: &mut Frame, app: &mut App, area: Rect, block_sty
 fn render_right_pane(frame: &mut Frame, app: &mut App, area: Rect) {
     let block_style = Style::default().fg(app.color_scheme.primary_color());
 
-    if app.mode == Mode::Settings {
+    if matches!(app.mode, Mode::Settings | Mode::EditingStage) && app.settings_section == SettingsSection::Pipeline {
+        render_stage_details(frame, app, area, block_style);
+    } else if app.mode == Mode::Settings {
         render_settings_preview(frame, app, area);
-    } else if let Some(editor) = app.editor.as_mut() {
-        frame.render_widget(&*editor, area);
+    } else if app.mode == Mode::Tasks {
+        render_task_context(frame, app, area, block_style);
+    } else if app.mode == Mode::Timeline {
+        render_timeline_detail(frame, app, area, block_style);
+    } else if app.mode == Mode::SelectingTemplate {
+        render_template_preview(frame, app, area, block_style);
+    } else if app.editor_visible() && app.active_buffer().is_some() {
+        render_editor_pane(frame, app, area);
     } else {
         render_file_preview(frame, app, area, block_style);
     }
 }
 
+fn render_editor_pane(frame: &mut Frame, app: &mut App, area: Rect) {
+    let find_height = if app.mode == Mode::Finding { 4 } else { 0 };
+    let editor_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0), Constraint::Length(find_height)])
+        .split(area);
+
+    render_buffer_tabs(frame, app, editor_layout[0]);
+    if let Some(split) = app.split.as_ref() {
+        let direction = match split.direction {
+            SplitDirection::Vertical => Direction::Horizontal,
+            SplitDirection::Horizontal => Direction::Vertical,
+        };
+        let pane_layout = Layout::default()
+            .direction(direction)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(editor_layout[1]);
+        let (first, second) = split.panes(app.active_buffer);
+        let active = app.active_buffer;
+        for (index, pane_area) in [(first, pane_layout[0]), (second, pane_layout[1])] {
+            if let Some(buffer) = app.buffers.get_mut(index) {
+                render_editor(frame, buffer, pane_area, app.color_scheme, &app.editor_options, index == active);
+            }
+        }
+    } else if let Some(buffer) = app.buffers.get_mut(app.active_buffer) {
+        render_editor(frame, buffer, editor_layout[1], app.color_scheme, &app.editor_options, true);
+    }
+    if app.mode == Mode::Finding {
+        render_find_bar(frame, app, editor_layout[2]);
+    }
+}
+
+fn render_buffer_tabs(frame: &mut Frame, app: &App, area: Rect) {
+    let titles: Vec<String> = app.buffers.iter().map(|buffer| buffer.title()).collect();
+    let tabs = Tabs::new(titles)
+        .select(app.active_buffer)
+        .style(Style::default().fg(app.color_scheme.primary_color()).bg(Color::Black))
+        .highlight_style(
+            Style::default()
+                .bg(app.color_scheme.primary_color())
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .divider("│");
+    frame.render_widget(tabs, area);
+}
+
+fn render_find_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let active = Style::default().fg(Color::Black).bg(app.color_scheme.primary_color());
+    let inactive = Style::default().fg(app.color_scheme.primary_color());
+    let (query_style, replace_style) = match app.find.field {
+        FindField::Query => (active, inactive),
+        FindField::Replacement => (inactive, active),
+    };
+    let flag = |on: bool, label: &'static str| {
+        if on {
+            Span::styled(format!("[{}]", label), Style::default().fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD))
+        } else {
+            Span::styled(format!(" {} ", label), Style::default().fg(Color::DarkGray))
+        }
+    };
+    let status = match &app.find.error {
+        Some(err) => Span::styled(format!(" ERR: {}", err.lines().last().unwrap_or_default()), Style::default().fg(Color::Red)),
+        None => Span::styled(format!(" {} MATCHES", app.find.match_count), inactive),
+    };
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(" FIND:    ", inactive),
+            Span::styled(format!("{} ", app.find.query), query_style),
+            Span::raw(" "),
+            flag(app.find.use_regex, ".*"),
+            flag(app.find.case_sensitive, "Aa"),
+            status,
+        ]),
+        Line::from(vec![
+            Span::styled(" REPLACE: ", inactive),
+            Span::styled(format!("{} ", app.find.replacement), replace_style),
+        ]),
+    ];
+
+    let find_widget = Paragraph::new(lines)
+        .style(Style::default().bg(Color::Black))
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .title(" ■■■ FIND / REPLACE ■■■ ")
+                .border_style(inactive)
+                .border_type(BorderType::Double),
+        );
+    frame.render_widget(find_widget, area);
+}
+
+fn render_pipeline_editor(frame: &mut Frame, app: &mut App, area: Rect, block_style: Style) {
+    let items: Vec<ListItem> = app.stages.iter().enumerate().map(|(i, stage)| {
+        ListItem::new(format!("{:02} {} {} → {}", i + 1, stage.icon, stage.name, stage.transitions_label()))
+            .style(Style::default().fg(app.stage_color(stage)))
+    }).collect();
+
+    let block = Block::default()
+        .title(format!(" ■■■ WORKFLOW PIPELINE ({}) ■■■ ", app.layer_of(Setting::Stages).label().to_uppercase()))
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.color_scheme.primary_color())
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, area, &mut app.settings_selection_state);
+}
+
+fn render_stage_details(frame: &mut Frame, app: &App, area: Rect, block_style: Style) {
+    let block = Block::default()
+        .title(" ■■■ STAGE DETAILS ■■■ ")
+        .borders(Borders::ALL)
+        .border_style(block_style)
+        .border_type(BorderType::Double);
+
+    let Some(stage) = app.selected_stage_index().map(|index| &app.stages[index]) else {
+        let hint = app.keymap.key_hint(Mode::Settings, Action::AddStage, false).map(|keys| format!(" Press {} to add one.", keys)).unwrap_or_default();
+        let empty = Paragraph::new(format!("No stages defined.{}", hint)).style(block_style).block(block);
+        frame.render_widget(empty, area);
+        return;
+    };
+
+    let notes = fs::read_dir(&stage.folder).map(|entries| entries.count()).unwrap_or(0);
+    let color = if stage.color == Color::Reset { "theme".to_string() } else { stage.color.to_string() };
+    let next = if stage.transitions.is_none() { "any stage".to_string() } else { stage.transitions_label() };
+    let details = format!(
+        "\nName:   {}\nIcon:   {}\nColor:  {}\nFolder: {}\nNext:   {}\nNotes:  {}\nWIP:    {}\nPre:    {}\nPost:   {}\nFrom:   {}",
+        stage.name,
+        stage.icon,
+        color,
+        stage.folder.to_string_lossy(),
+        next,
+        notes,
+        stage.wip_limit.map(|limit| limit.to_string()).unwrap_or_else(|| "none".to_string()),
+        stage.pre_hook.as_deref().unwrap_or("none"),
+        stage.post_hook.as_deref().unwrap_or("none"),
+        app.layer_of(Setting::Stages).label()
+    );
+    let mut lines: Vec<Line> = details.lines().map(|line| Line::from(line.to_string())).collect();
+    lines.extend(untrusted_hook_lines(app));
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(app.stage_color(stage)))
+        .block(block);
+    frame.render_widget(paragraph, area);
+}
+
 fn render_settings_preview(frame: &mut Frame, app: &App, area: Rect) {
     let schemes = ColorScheme::all_schemes();
     let selected_scheme = schemes.get(app.settings_selection_state.selected().unwrap_or(0))
@@ -271,28 +931,75 @@ fn render_settings_preview(frame: &mut Frame, app: &App, area: Rect) {
         .border_style(Style::default().fg(selected_scheme.primary_color()))
         .border_type(BorderType::Double);
 
-    let info_paragraph = Paragraph::new(info_content)
+    let mut info_text = Text::from(info_content);
+    info_text.extend(setting_source_lines(app));
+    let info_paragraph = Paragraph::new(info_text)
         .style(Style::default().fg(selected_scheme.primary_color()))
         .block(info_block);
 
     frame.render_widget(info_paragraph, area);
 }
 
-fn render_file_preview(frame: &mut Frame, app: &App, area: Rect, block_style: Style) {
-    let (title, content) = if let Some(selected_index) = app.file_list_state.selected() {
-        let current_files = app.get_current_files();
-        current_files.get(selected_index)
-            .and_then(|path| {
-                if path.is_dir() {
-                    Some(("[DIR]".to_string(), path.to_string_lossy().into_owned()))
-                } else {
-                    fs::read_to_string(path).ok().map(|content| (path.file_name().unwrap_or_default().to_string_lossy().into_owned(), content))
-                }
-            })
-            .map(|(name, content)| (format!(" ■■■ VIEWING: {} ■■■ ", name), content))
-            .unwrap_or_else(|| (" ■■■ WEYLAND-YUTANI CORP - FILE VIEWER ■■■ ".to_string(), "\n\n\n\n████████████████████\n█     < PREVIEW NOT AVAILABLE >     █\n████████████████████".to_string()))
+/// Each setting a vault can override, with its current value and the layer it comes from
+/// Every hook the vault config would run, listed for review until the vault is trusted
+fn untrusted_hook_lines(app: &App) -> Vec<Line<'static>> {
+    if !app.hooks_need_trust() {
+        return Vec::new();
+    }
+    let warning = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+    let heading = if app.vault_hooks_changed() { "VAULT HOOKS (CHANGED SINCE TRUSTED, WON'T RUN)" } else { "VAULT HOOKS (NOT TRUSTED, WON'T RUN)" };
+    let mut lines = vec![Line::default(), Line::styled(heading, warning)];
+    for stage in &app.stages {
+        for (phase, hook) in [("pre", &stage.pre_hook), ("post", &stage.post_hook)] {
+            if let Some(hook) = hook {
+                lines.push(Line::from(format!("{} {}: {}", stage.name, phase, hook)));
+            }
+        }
+    }
+    if let Some(key) = app.keymap.keys_for(Mode::Settings, Action::TrustVaultHooks, false).first() {
+        lines.push(Line::styled(format!("Press {} to trust this vault and let them run", key), warning));
+    }
+    lines
+}
+
+fn setting_source_lines(app: &App) -> Vec<Line<'static>> {
+    let heading = match &app.vault {
+        Some(vault) => format!("SETTING SOURCES (VAULT: {})", vault.display()),
+        None => "SETTING SOURCES (NO VAULT)".to_string(),
+    };
+    let mut lines = vec![Line::default(), Line::styled(heading, Style::default().add_modifier(Modifier::BOLD))];
+    for setting in Setting::ALL {
+        let value = match setting {
+            Setting::Theme => app.color_scheme.name().to_string(),
+            Setting::TemplateRoot => app.template_root.as_ref().map(|path| path.display().to_string()).unwrap_or_else(|| "not set".to_string()),
+            Setting::Stages => format!("{} stages", app.stages.len()),
+            Setting::Ignore if app.ignore.patterns.is_empty() => "nothing".to_string(),
+            Setting::Ignore => app.ignore.patterns.join(", "),
+        };
+        lines.push(Line::from(format!("{:<14} {:<24} {}", setting.label(), value, app.layer_of(setting).label())));
+    }
+    lines
+}
+
+fn render_file_preview(frame: &mut Frame, app: &mut App, area: Rect, block_style: Style) {
+    app.preview_height = area.height.saturating_sub(2);
+
+    let (title, content) = if let Some(cache) = app.preview_cache.as_ref() {
+        let name = cache.path.file_name().unwrap_or_default().to_string_lossy();
+        let scroll = app.current_preview_scroll();
+        let visible = cache.lines.iter().skip(scroll).take(app.preview_height as usize);
+        let title = if app.mode == Mode::PreviewFind {
+            format!(" ■■■ VIEWING: {} ■■■ FIND: {}█ ", name, app.preview_find)
+        } else {
+            format!(" ■■■ VIEWING: {} ■■■ ", name)
+        };
+        (title, highlight_preview_lines(visible, &app.preview_find, app.color_scheme))
+    } else if let Some(path) = app.selected_file().filter(|path| path.is_dir()) {
+        (" ■■■ VIEWING: [DIR] ■■■ ".to_string(), Text::from(path.to_string_lossy().into_owned()))
+    } else if app.selected_file().is_some() {
+        (" ■■■ WEYLAND-YUTANI CORP - FILE VIEWER ■■■ ".to_string(), Text::from("\n\n\n\n████████████████████\n█     < PREVIEW NOT AVAILABLE >     █\n████████████████████"))
     } else {
-        (" ■■■ WEYLAND-YUTANI CORP - FILE VIEWER ■■■ ".to_string(), "\n\n\n████████████████████████████████\n█  < SELECT A FILE FROM THE MU-TH-UR >  █\n█  < 6000 MAINFRAME ARCHIVE SYSTEM >  █\n████████████████████████████████".to_string())
+        (" ■■■ WEYLAND-YUTANI CORP - FILE VIEWER ■■■ ".to_string(), Text::from("\n\n\n████████████████████████████████\n█  < SELECT A FILE FROM THE MU-TH-UR >  █\n█  < 6000 MAINFRAME ARCHIVE SYSTEM >  █\n████████████████████████████████"))
     };
 
     let block = Block::default()
@@ -306,18 +1013,183 @@ fn render_file_preview(frame: &mut Frame, app: &App, area: Rect, block_style: St
         .block(block);
 
     frame.render_widget(paragraph, area);
+
+    let total_lines = app.preview_lines().len();
+    if total_lines > app.preview_height as usize {
+        let mut scrollbar_state = ScrollbarState::new(total_lines.saturating_sub(app.preview_height as usize))
+            .position(app.current_preview_scroll());
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .style(Style::default().fg(app.color_scheme.primary_color()))
+            .begin_symbol(Some("▲"))
+            .end_symbol(Some("▼"));
+        frame.render_stateful_widget(scrollbar, area.inner(Margin { vertical: 1, horizontal: 0 }), &mut scrollbar_state);
+    }
+}
+
+/// Build preview text for the visible lines, marking every occurrence of the in-preview query
+fn highlight_preview_lines<'l>(lines: impl Iterator<Item = &'l String>, query: &str, color_scheme: ColorScheme) -> Text<'l> {
+    let matcher = if query.is_empty() {
+        None
+    } else {
+        RegexBuilder::new(&regex::escape(query)).case_insensitive(true).build().ok()
+    };
+    let match_style = Style::default().fg(Color::Black).bg(color_scheme.secondary_color());
+
+    lines.map(|line| {
+        let Some(matcher) = matcher.as_ref() else {
+            return Line::from(line.as_str());
+        };
+        let mut spans = Vec::new();
+        let mut last = 0;
+        for found in matcher.find_iter(line) {
+            spans.push(Span::raw(&line[last..found.start()]));
+            spans.push(Span::styled(found.as_str(), match_style));
+            last = found.end();
+        }
+        spans.push(Span::raw(&line[last..]));
+        Line::from(spans)
+    }).collect()
 }
 
 fn render_popups(frame: &mut Frame, app: &mut App, current_time: u64) {
     match app.mode {
         Mode::Naming => render_naming_popup(frame, app, current_time),
         Mode::Renaming => render_rename_popup(frame, app, current_time),
+        Mode::TemplatePrompt => render_template_prompt_popup(frame, app, current_time),
+        Mode::EditingStage => render_stage_edit_popup(frame, app, current_time),
+        Mode::HookLog => render_hook_log_popup(frame, app),
+        Mode::ConfigErrors => render_config_errors_popup(frame, app),
         Mode::ConfirmingDelete => render_delete_confirmation_popup(frame, app),
+        Mode::ConfirmingQuit => render_quit_confirmation_popup(frame, app),
+        Mode::ConfirmingBatch => render_batch_confirmation_popup(frame, app),
+        Mode::Tagging => render_tagging_popup(frame, app, current_time),
         Mode::SelectingMoveDestination => render_move_destination_popup(frame, app),
+        Mode::Calendar => render_calendar_popup(frame, app),
+        Mode::Switcher => render_switcher_popup(frame, app, current_time),
+        Mode::VaultSwitcher => render_vault_switcher_popup(frame, app),
+        Mode::CommandPalette => render_command_palette(frame, app, current_time),
         _ => {}
     }
 }
 
+fn render_command_palette(frame: &mut Frame, app: &mut App, current_time: u64) {
+    let primary = Style::default().fg(app.color_scheme.primary_color());
+    let secondary = Style::default().fg(app.color_scheme.secondary_color());
+    let Some(palette) = app.palette.as_mut() else {
+        return;
+    };
+    let height = (palette.entries.len() as u16 + 5).clamp(8, 24);
+    let area = centered_rect(70, height, frame.area());
+    frame.render_widget(Clear, area);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let cursor_char = if current_time.is_multiple_of(2) { "█" } else { " " };
+    let title = format!(" ■■■ COMMANDS: {} ■■■ ", palette.return_mode.to_string());
+    let filter = Paragraph::new(format!("{}{}", palette.filter, cursor_char))
+        .style(primary.bg(Color::Black))
+        .block(Block::default().borders(Borders::ALL).border_style(primary).title(title).border_type(BorderType::Double));
+    frame.render_widget(filter, layout[0]);
+
+    let width = layout[1].width.saturating_sub(6) as usize;
+    let items: Vec<ListItem> = if palette.entries.is_empty() {
+        vec![ListItem::new("No matching commands").style(secondary)]
+    } else {
+        palette.entries.iter().map(|entry| {
+            let title = entry.action.title();
+            let padding = width.saturating_sub(title.chars().count() + entry.keys.chars().count()).max(1);
+            ListItem::new(Line::from(vec![
+                Span::styled(title, primary),
+                Span::raw(" ".repeat(padding)),
+                Span::styled(entry.keys.clone(), secondary),
+            ]))
+        }).collect()
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(primary).border_type(BorderType::Double))
+        .highlight_style(Style::default().bg(app.color_scheme.primary_color()).fg(Color::Black).add_modifier(Modifier::BOLD))
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, layout[1], &mut palette.list_state);
+}
+
+fn render_switcher_popup(frame: &mut Frame, app: &mut App, current_time: u64) {
+    let primary = Style::default().fg(app.color_scheme.primary_color());
+    let secondary = Style::default().fg(app.color_scheme.secondary_color());
+    let Some(switcher) = app.switcher.as_mut() else {
+        return;
+    };
+    let height = (switcher.entries.len() as u16 + 5).clamp(8, 20);
+    let area = centered_rect(70, height, frame.area());
+    frame.render_widget(Clear, area);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let cursor_char = if current_time.is_multiple_of(2) { "█" } else { " " };
+    let filter = Paragraph::new(format!("{}{}", switcher.filter, cursor_char))
+        .style(primary.bg(Color::Black))
+        .block(Block::default().borders(Borders::ALL).border_style(primary).title(" ■■■ QUICK SWITCH ■■■ ").border_type(BorderType::Double));
+    frame.render_widget(filter, layout[0]);
+
+    let items: Vec<ListItem> = if switcher.entries.is_empty() {
+        vec![ListItem::new("No pinned or recent notes").style(secondary)]
+    } else {
+        switcher.entries.iter().map(|entry| {
+            let (icon, style) = if entry.pinned { ("★", secondary.add_modifier(Modifier::BOLD)) } else { ("↺", primary) };
+            let kind = if entry.path.is_dir() { "[DIR] " } else { "" };
+            let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+            let parent = entry.path.parent().map(|parent| parent.to_string_lossy().to_string()).unwrap_or_default();
+            ListItem::new(format!("{} {}{} — {}", icon, kind, name, parent)).style(style)
+        }).collect()
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(primary).title(" ■■■ ★ PINNED  ↺ RECENT ■■■ ").border_type(BorderType::Double))
+        .highlight_style(Style::default().bg(app.color_scheme.primary_color()).fg(Color::Black).add_modifier(Modifier::BOLD))
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, layout[1], &mut switcher.list_state);
+}
+
+fn render_vault_switcher_popup(frame: &mut Frame, app: &mut App) {
+    let primary = Style::default().fg(app.color_scheme.primary_color());
+    let secondary = Style::default().fg(app.color_scheme.secondary_color());
+    let current = app.current_vault();
+    let Some(switcher) = app.vault_switcher.as_mut() else {
+        return;
+    };
+    let error_height = if switcher.error.is_some() { 3 } else { 0 };
+    let height = (app.vaults.len() as u16 + 2).clamp(5, 18) + error_height;
+    let area = centered_rect(70, height, frame.area());
+    frame.render_widget(Clear, area);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(error_height)])
+        .split(area);
+
+    let items: Vec<ListItem> = if app.vaults.is_empty() {
+        vec![ListItem::new("No vaults yet — add [[vaults]] entries to config.toml").style(secondary)]
+    } else {
+        app.vaults.iter().enumerate().map(|(index, vault)| {
+            let (icon, style) = if current == Some(index) { ("●", secondary.add_modifier(Modifier::BOLD)) } else { ("○", primary) };
+            ListItem::new(format!("{} {} — {}", icon, vault.name, vault.path.display())).style(style)
+        }).collect()
+    };
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(primary).title(" ■■■ VAULTS ■■■ ").border_type(BorderType::Double))
+        .highlight_style(Style::default().bg(app.color_scheme.primary_color()).fg(Color::Black).add_modifier(Modifier::BOLD))
+        .highlight_symbol("► ");
+    frame.render_stateful_widget(list, layout[0], &mut switcher.list_state);
+
+    if let Some(error) = &switcher.error {
+        let message = Paragraph::new(error.as_str())
+            .style(Style::default().fg(Color::Red))
+            .block(Block::default().borders(Borders::ALL).border_style(primary).border_type(BorderType::Double));
+        frame.render_widget(message, layout[1]);
+    }
+}
+
 fn render_naming_popup(frame: &mut Frame, app: &App, current_time: u64) {
     let area = centered_rect(70, 3, frame.area());
     let title = if app.pending_template.is_some() {
@@ -334,6 +1206,62 @@ fn render_naming_popup(frame: &mut Frame, app: &App, current_time: u64) {
     frame.render_widget(input_widget, area);
 }
 
+fn render_template_prompt_popup(frame: &mut Frame, app: &App, current_time: u64) {
+    let Some(pending) = app.pending_note.as_ref() else {
+        return;
+    };
+    let area = centered_rect(70, 3, frame.area());
+    let title = format!(
+        " ■■■ {} ({}/{}) ■■■ ",
+        pending.current_prompt().unwrap_or_default().to_uppercase(),
+        pending.answers.len() + 1,
+        pending.prompts.len()
+    );
+    let cursor_char = if current_time.is_multiple_of(2) { "█" } else { " " };
+    let input_display = format!("{}{}", pending.input, cursor_char);
+    let input_widget = Paragraph::new(input_display)
+        .style(Style::default().fg(app.color_scheme.primary_color()).bg(Color::Black))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.color_scheme.primary_color())).title(title).border_type(BorderType::Double));
+    frame.render_widget(Clear, area);
+    frame.render_widget(input_widget, area);
+}
+
+fn render_stage_edit_popup(frame: &mut Frame, app: &App, current_time: u64) {
+    let area = centered_rect(70, 3, frame.area());
+    let title = format!(" ■■■ {} ■■■ ", app.stage_field.label());
+    let cursor_char = if current_time.is_multiple_of(2) { "█" } else { " " };
+    let input_display = format!("{}{}", app.filename_input, cursor_char);
+    let input_widget = Paragraph::new(input_display)
+        .style(Style::default().fg(app.color_scheme.primary_color()).bg(Color::Black))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.color_scheme.primary_color())).title(title).border_type(BorderType::Double));
+    frame.render_widget(Clear, area);
+    frame.render_widget(input_widget, area);
+}
+
+fn render_hook_log_popup(frame: &mut Frame, app: &App) {
+    let height = (app.hook_log.len() as u16 + 2).clamp(5, 20);
+    let area = centered_rect(80, height, frame.area());
+    let lines: Vec<Line> = app.hook_log.iter().map(|line| {
+        let style = if line.starts_with("$ ") {
+            Style::default().fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.color_scheme.primary_color())
+        };
+        Line::styled(line.as_str(), style)
+    }).collect();
+    // Keep the end of long output visible, since that's where failures are reported
+    let scroll = (app.hook_log.len() as u16).saturating_sub(height.saturating_sub(2));
+    let paragraph = Paragraph::new(lines)
+        .scroll((scroll, 0))
+        .block(Block::default()
+            .borders(Borders::ALL)
+            .title(" ■■■ STAGE HOOK OUTPUT ■■■ ")
+            .border_style(Style::default().fg(app.color_scheme.primary_color()))
+            .border_type(BorderType::Double));
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
 fn render_rename_popup(frame: &mut Frame, app: &App, current_time: u64) {
     let area = centered_rect(70, 3, frame.area());
     let title = " ■■■ RENAME FILE ■■■ ";
@@ -375,18 +1303,144 @@ fn render_delete_confirmation_popup(frame: &mut Frame, app: &App) {
     frame.render_widget(delete_widget, area);
 }
 
+fn render_batch_confirmation_popup(frame: &mut Frame, app: &App) {
+    let height = (app.marked.len() as u16 + 6).min(20);
+    let area = centered_rect(60, height, frame.area());
+    let danger = matches!(app.pending_batch, Some(BatchAction::Delete));
+    let color = if danger { Color::Red } else { app.color_scheme.primary_color() };
+
+    let mut lines = vec![
+        Line::styled(format!("{} {} FILES:", app.batch_description(), app.marked.len()), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+        Line::default(),
+    ];
+    let visible = height.saturating_sub(6) as usize;
+    lines.extend(app.marked.iter().take(visible).map(|path| {
+        Line::styled(format!("  ■ {}", path.file_name().unwrap_or_default().to_string_lossy()), Style::default().fg(color))
+    }));
+    if app.marked.len() > visible {
+        lines.push(Line::styled(format!("  … and {} more", app.marked.len() - visible), Style::default().fg(Color::DarkGray)));
+    }
+    lines.push(Line::default());
+    lines.push(Line::styled("[Y] CONFIRM     [N/ESC] CANCEL", Style::default().fg(color).add_modifier(Modifier::BOLD)));
+
+    let popup = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" ■■■ BATCH OPERATION ■■■ ")
+            .border_style(Style::default().fg(color))
+            .border_type(BorderType::Double),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(popup, area);
+}
+
+fn render_tagging_popup(frame: &mut Frame, app: &App, current_time: u64) {
+    let area = centered_rect(70, 3, frame.area());
+    let title = format!(" ■■■ ADD TAG TO {} FILE(S) ■■■ ", app.batch_targets().len());
+    let cursor_char = if current_time.is_multiple_of(2) { "█" } else { " " };
+    let input_display = format!("#{}{}", app.filename_input, cursor_char);
+    let input_widget = Paragraph::new(input_display)
+        .style(Style::default().fg(app.color_scheme.primary_color()).bg(Color::Black))
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(app.color_scheme.primary_color())).title(title).border_type(BorderType::Double));
+    frame.render_widget(Clear, area);
+    frame.render_widget(input_widget, area);
+}
+
+fn render_quit_confirmation_popup(frame: &mut Frame, app: &App) {
+    let unsaved: Vec<String> = app.buffers
+        .iter()
+        .filter(|buffer| buffer.is_modified())
+        .map(|buffer| buffer.path.file_name().unwrap_or_default().to_string_lossy().into_owned())
+        .collect();
+    let area = centered_rect(60, 8 + unsaved.len() as u16, frame.area());
+
+    let mut content = String::from("\n    ⚠  UNSAVED BUFFERS IN MEMORY  ⚠\n\n");
+    for name in &unsaved {
+        content.push_str(&format!("    ● {}\n", name));
+    }
+    content.push_str("\n    [S] SAVE ALL & QUIT   [D] DISCARD & QUIT   [ESC] CANCEL\n");
+
+    let quit_widget = Paragraph::new(content)
+        .style(Style::default().fg(Color::Red).bg(Color::Black).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" ■■■ WEYLAND-YUTANI SECURITY PROTOCOL ■■■ ")
+                .border_style(Style::default().fg(Color::Red))
+                .border_type(BorderType::Double)
+        );
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(quit_widget, area);
+}
+
+fn render_calendar_popup(frame: &mut Frame, app: &App) {
+    let Some(calendar) = app.calendar.as_ref() else {
+        return;
+    };
+    let area = centered_rect(40, 13, frame.area());
+    let primary = Style::default().fg(app.color_scheme.primary_color());
+    let today = Local::now().date_naive();
+
+    let mut lines = vec![
+        Line::styled(calendar.selected.format("%B %Y").to_string().to_uppercase(), primary.add_modifier(Modifier::BOLD)),
+        Line::default(),
+        Line::styled(" Mo  Tu  We  Th  Fr  Sa  Su ", primary.add_modifier(Modifier::BOLD)),
+    ];
+    let mut day = grid_start(calendar.selected);
+    for _ in 0..6 {
+        let mut spans = Vec::new();
+        for _ in 0..7 {
+            let marker = if calendar.open_tasks.contains(&day) { "•" } else { " " };
+            let mut style = if day.month() != calendar.selected.month() {
+                Style::default().fg(Color::DarkGray)
+            } else if calendar.entries.contains(&day) {
+                Style::default().fg(app.color_scheme.secondary_color()).add_modifier(Modifier::BOLD)
+            } else {
+                primary
+            };
+            if day == today {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if day == calendar.selected {
+                style = style.bg(app.color_scheme.primary_color()).fg(Color::Black);
+            }
+            spans.push(Span::styled(format!(" {:>2}{}", day.day(), marker), style));
+            day += chrono::Duration::days(1);
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::styled("bold: entry  •: open tasks  underline: today", Style::default().fg(Color::DarkGray)));
+
+    let block = Block::default()
+        .title(" ■■■ JOURNAL CALENDAR ■■■ ")
+        .borders(Borders::ALL)
+        .border_style(primary)
+        .border_type(BorderType::Double);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center).block(block), area);
+}
+
 fn render_move_destination_popup(frame: &mut Frame, app: &mut App) {
     let area = centered_rect(50, 8, frame.area());
     let filename = app.operation_target_file
         .as_ref()
         .and_then(|p| p.file_name())
         .map(|n| n.to_string_lossy().into_owned())
-        .unwrap_or_else(|| "Unknown".to_string());
+        .unwrap_or_else(|| format!("{} MARKED FILES", app.marked.len()));
     
-    let items: Vec<ListItem> = app.move_destinations
+    let mut items: Vec<ListItem> = app.move_options
         .iter()
-        .map(|dest| ListItem::new(dest.as_str()).style(Style::default().fg(app.color_scheme.primary_color())))
+        .map(|&index| {
+            let stage = &app.stages[index];
+            ListItem::new(format!("{} {}", stage.icon, stage.name)).style(Style::default().fg(app.stage_color(stage)))
+        })
         .collect();
+    if items.is_empty() {
+        items.push(ListItem::new("No legal next stage").style(Style::default().fg(Color::DarkGray)));
+    }
     
     let block_style = Style::default().fg(app.color_scheme.primary_color());
     let list = List::new(items)
@@ -444,24 +1498,7 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect, current_time: u64
     frame.render_widget(mode_bar, status_layout[1]);
 
     // Controls Line
-    let controls_text = match app.mode {
-        Mode::Normal => "▶ NAV: ↑/↓/←/→ ▶ NEW: n ▶ RENAME: r ▶ SEARCH: / ▶ TMPL: Shift+T ▶ CHDIR: c ▶ DEL: d ▶ MOVE: m ▶ SETTINGS: s ▶ QUIT: q",
-        Mode::Editing => "▶ SAVE & EXIT: Esc ▶ COPY: Ctrl+C",
-        Mode::Naming => "▶ CONFIRM: Enter ▶ CANCEL: Esc",
-        Mode::Renaming => "▶ CONFIRM: Enter ▶ CANCEL: Esc",
-        Mode::ChangingDirectory => "▶ SELECT: s ▶ NAVIGATE: ↑/↓/Enter ▶ CANCEL: Esc",
-        Mode::SelectingTemplateFolder => "▶ SELECT: s ▶ NAVIGATE: ↑/↓/Enter ▶ CANCEL: Esc",
-        Mode::SelectingTemplate => "▶ SELECT: Enter ▶ CANCEL: Esc",
-        Mode::Search => if app.search_input_mode {
-            "▶ TYPE QUERY ▶ LOCK INPUT: Enter ▶ CANCEL SEARCH: Esc"
-        } else {
-            "▶ NAV: ↑/↓/←/→ ▶ EDIT QUERY: / ▶ DEL: d ▶ MOVE: m ▶ RENAME: r ▶ OPEN: Enter/→ ▶ EXIT: Esc"
-        },
-        Mode::ConfirmingDelete => "▶ CONFIRM: Y/Enter ▶ CANCEL: N/Esc",
-        Mode::SelectingMoveDestination => "▶ SELECT: Enter ▶ NAVIGATE: ↑/↓ ▶ CANCEL: Esc",
-        Mode::Settings => "▶ APPLY: Enter ▶ NAVIGATE: ↑/↓ ▶ CANCEL: Esc",
-    };
-    
+    let controls_text = app.controls_line();
     let controls_bar = Paragraph::new(controls_text)
         .style(Style::default().fg(app.color_scheme.primary_color()).bg(Color::Black));
     frame.render_widget(controls_bar, status_layout[2]);
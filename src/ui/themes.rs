@@ -1,5 +1,7 @@
 use ratatui::prelude::*;
 
+use crate::highlight::Token;
+
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum ColorScheme {
     Green,      // Classic green terminal
@@ -60,19 +62,32 @@ impl ColorScheme {
         ]
     }
 
-    pub fn from_string(s: &str) -> Self {
-        match s {
-            "Blue" => ColorScheme::Blue,
-            "Amber" => ColorScheme::Amber,
-            "Orange" => ColorScheme::Orange,
-            "LightGreen" => ColorScheme::LightGreen,
-            "Red" => ColorScheme::Red,
-            "BrightRed" => ColorScheme::BrightRed,
-            _ => ColorScheme::Green,
-        }
+    pub fn from_string(s: &str) -> Option<Self> {
+        Self::all_schemes().into_iter().find(|scheme| scheme.to_string() == s)
     }
 
     pub fn to_string(&self) -> String {
         format!("{:?}", self)
     }
-}
\ No newline at end of file
+
+    pub fn highlight_style(&self, token: Token) -> Style {
+        let primary = Style::default().fg(self.primary_color());
+        let secondary = Style::default().fg(self.secondary_color());
+        let dim = Style::default().fg(Color::DarkGray);
+        match token {
+            Token::Heading => secondary.add_modifier(Modifier::BOLD),
+            Token::Emphasis => primary.add_modifier(Modifier::ITALIC),
+            Token::Strong => primary.add_modifier(Modifier::BOLD),
+            Token::Link => secondary.add_modifier(Modifier::UNDERLINED),
+            Token::InlineCode => Style::default().fg(Color::Black).bg(self.secondary_color()),
+            Token::ListMarker => secondary.add_modifier(Modifier::BOLD),
+            Token::BlockQuote => secondary.add_modifier(Modifier::ITALIC),
+            Token::Frontmatter | Token::CodeFence => dim,
+            Token::CodeText => secondary,
+            Token::Keyword => primary.add_modifier(Modifier::BOLD),
+            Token::String => secondary.add_modifier(Modifier::ITALIC),
+            Token::Comment => dim.add_modifier(Modifier::ITALIC),
+            Token::Number => primary,
+        }
+    }
+}
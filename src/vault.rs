@@ -0,0 +1,462 @@
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
+use ratatui::widgets::ListState;
+use sha2::{Digest, Sha256};
+use toml_edit::{DocumentMut, Item, Table, TableLike};
+
+use crate::app::App;
+use crate::bookmarks::absolute_path;
+use crate::config::{self, set, set_or_remove, string_array, Section};
+use crate::file_ops::IgnoreList;
+use crate::modes::Mode;
+use crate::ui::themes::ColorScheme;
+use crate::workflow::{default_stages, read_stages, write_stages, WorkflowStage};
+
+/// A vault is any folder holding this file; it applies to that folder and everything below it
+pub const VAULT_CONFIG: &str = ".nostromo/config.toml";
+
+/// Where a setting's current value comes from
+#[derive(PartialEq, Clone, Copy)]
+pub enum Layer {
+    Default,
+    Global,
+    Vault,
+}
+
+/// The settings a vault config may override
+#[derive(PartialEq, Clone, Copy)]
+pub enum Setting {
+    TemplateRoot,
+    Theme,
+    Stages,
+    Ignore,
+}
+
+/// The overridable settings one config file sets; `None` leaves the setting to the layer below
+#[derive(Default)]
+pub struct LayerSettings {
+    pub template_root: Option<PathBuf>,
+    pub color_scheme: Option<ColorScheme>,
+    pub stages: Option<Vec<WorkflowStage>>,
+    pub ignore: Option<Vec<String>>,
+}
+
+/// A vault listed under `[[vaults]]`, along with the session it was last left in
+pub struct NamedVault {
+    pub name: String,
+    pub path: PathBuf,
+    pub last_file: Option<PathBuf>,
+    pub open_buffers: Vec<PathBuf>,
+}
+
+pub struct VaultSwitcherState {
+    pub list_state: ListState,
+    pub error: Option<String>,
+}
+
+impl Layer {
+    pub fn label(self) -> &'static str {
+        match self {
+            Layer::Default => "default",
+            Layer::Global => "global config",
+            Layer::Vault => "vault config",
+        }
+    }
+}
+
+impl Setting {
+    pub const ALL: [Setting; 4] = [Setting::Theme, Setting::TemplateRoot, Setting::Stages, Setting::Ignore];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Setting::TemplateRoot => "Template root",
+            Setting::Theme => "Theme",
+            Setting::Stages => "Pipeline",
+            Setting::Ignore => "Ignored",
+        }
+    }
+}
+
+/// `path` relative to `base` when it lies inside it, so a vault keeps working if it's moved
+pub fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    match path.strip_prefix(base) {
+        Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+impl LayerSettings {
+    /// Relative paths are resolved against `base`
+    pub fn from_config(config: &Section, base: &Path, errors: &mut Vec<String>) -> Self {
+        let template_root = config.path("template_root", errors).map(|path| base.join(path)).filter(|path| {
+            if !path.is_dir() {
+                errors.push(format!("{}: {} is not a folder", config.key_path("template_root"), path.display()));
+            }
+            path.is_dir()
+        });
+        let color_scheme = config.str("theme", errors).and_then(|name| {
+            let scheme = ColorScheme::from_string(name);
+            if scheme.is_none() {
+                let names: Vec<String> = ColorScheme::all_schemes().iter().map(ColorScheme::to_string).collect();
+                errors.push(format!("{}: expected one of {}", config.key_path("theme"), names.join(", ")));
+            }
+            scheme
+        });
+        Self {
+            template_root,
+            color_scheme,
+            stages: read_stages(config, base, errors),
+            ignore: config
+                .strings("ignore", errors)
+                .map(|patterns| patterns.into_iter().map(String::from).collect()),
+        }
+    }
+}
+
+/// The `[[vaults]]` list; session paths are stored relative to their vault
+pub fn read_vaults(config: &Section, errors: &mut Vec<String>) -> Vec<NamedVault> {
+    let mut vaults: Vec<NamedVault> = Vec::new();
+    for vault in config.sections("vaults", errors) {
+        vault.check_keys(&["name", "path", "last_file", "open"], errors);
+        let Some(name) = vault.str("name", errors).map(str::trim).filter(|name| !name.is_empty()) else {
+            errors.push(format!("{}: a vault needs a name", vault.name));
+            continue;
+        };
+        if vaults.iter().any(|existing| existing.name == name) {
+            errors.push(format!("{}: '{}' is already the name of another vault", vault.key_path("name"), name));
+            continue;
+        }
+        let Some(path) = vault.path("path", errors) else {
+            errors.push(format!("{}: a vault needs a path", vault.name));
+            continue;
+        };
+        if !path.is_dir() {
+            errors.push(format!("{}: {} is not a folder", vault.key_path("path"), path.display()));
+            continue;
+        }
+        let path = absolute_path(&path);
+        vaults.push(NamedVault {
+            name: name.to_string(),
+            last_file: vault.path("last_file", errors).map(|file| path.join(file)),
+            open_buffers: vault.strings("open", errors).unwrap_or_default().into_iter().map(|file| path.join(file)).collect(),
+            path,
+        });
+    }
+    vaults
+}
+
+/// Update each vault's `last_file` and `open` in place; vaults are only ever added or removed by hand
+pub fn write_vault_sessions(vaults: &[NamedVault], config: &mut Table) {
+    let Some(tables) = config.get_mut("vaults").and_then(Item::as_array_of_tables_mut) else {
+        return;
+    };
+    let mut written = Vec::new();
+    for table in tables.iter_mut() {
+        let name = table.get("name").and_then(Item::as_str).map(str::trim);
+        // Later tables reusing a name were skipped when reading, so they're left alone here too
+        let Some(vault) = vaults.iter().find(|vault| Some(vault.name.as_str()) == name && !written.contains(&vault.name)) else {
+            continue;
+        };
+        written.push(vault.name.clone());
+        let relative = |path: &PathBuf| relative_to(path, &vault.path).to_string_lossy().to_string();
+        set_or_remove(table, "last_file", vault.last_file.as_ref().map(relative));
+        let open: Vec<String> = vault.open_buffers.iter().map(relative).collect();
+        set_or_remove(table, "open", (!open.is_empty()).then(|| string_array(open.iter().map(String::as_str))));
+    }
+}
+
+/// `[trusted_vaults]` maps each trusted vault's path to the hash of the hooks it was trusted with
+pub fn read_trusted_vaults(config: &Section, errors: &mut Vec<String>) -> BTreeMap<PathBuf, String> {
+    config
+        .keys()
+        .into_iter()
+        .filter_map(|vault| Some((PathBuf::from(vault), config.str(vault, errors)?.to_string())))
+        .collect()
+}
+
+pub fn write_trusted_vaults(trusted: &BTreeMap<PathBuf, String>, config: &mut Table) {
+    config.retain(|vault, _| trusted.contains_key(Path::new(vault)));
+    for (vault, hash) in trusted {
+        set(config, &vault.to_string_lossy(), hash.as_str());
+    }
+}
+
+/// A hash of every stage's hooks, so trusting a vault only covers the commands that were reviewed
+fn hooks_hash(stages: &[WorkflowStage]) -> String {
+    let mut hasher = Sha256::new();
+    for stage in stages {
+        for part in [Some(&stage.name), stage.pre_hook.as_ref(), stage.post_hook.as_ref()] {
+            hasher.update(part.map(String::as_str).unwrap_or_default());
+            hasher.update([0]);
+        }
+    }
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// The nearest folder at or above `root` that holds a vault config
+pub fn find_vault(root: &Path) -> Option<PathBuf> {
+    let root = fs::canonicalize(root).ok()?;
+    root.ancestors().find(|dir| dir.join(VAULT_CONFIG).is_file()).map(Path::to_path_buf)
+}
+
+/// Read a vault's overrides, prefixing any problems with the file they're in
+fn load_vault(vault: &Path, errors: &mut Vec<String>) -> LayerSettings {
+    let path = vault.join(VAULT_CONFIG);
+    let mut problems = Vec::new();
+    let settings = match fs::read_to_string(&path).map(|content| content.parse::<DocumentMut>()) {
+        Ok(Ok(doc)) => {
+            let config = Section::new(doc.as_table() as &dyn TableLike);
+            config.check_keys(&["version", "template_root", "theme", "stages", "ignore"], &mut problems);
+            LayerSettings::from_config(&config, vault, &mut problems)
+        }
+        Ok(Err(error)) => {
+            problems.push(error.to_string());
+            LayerSettings::default()
+        }
+        Err(error) => {
+            problems.push(error.to_string());
+            LayerSettings::default()
+        }
+    };
+    errors.extend(problems.into_iter().map(|problem| format!("{}: {}", path.display(), problem)));
+    settings
+}
+
+/// The vault's value if it sets one, otherwise the global one, with the layer it came from
+fn pick<T>(vault: Option<T>, global: Option<T>) -> (Option<T>, Layer) {
+    match (vault, global) {
+        (Some(value), _) => (Some(value), Layer::Vault),
+        (None, Some(value)) => (Some(value), Layer::Global),
+        (None, None) => (None, Layer::Default),
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn layer_of(&self, setting: Setting) -> Layer {
+        self.setting_layers[setting as usize]
+    }
+
+    /// Load the vault around the current root, if any, on top of the global settings; returns the vault's problems
+    pub fn apply_layers(&mut self, global: LayerSettings) -> Vec<String> {
+        let mut errors = Vec::new();
+        self.vault = find_vault(&self.root);
+        let vault = self.vault.as_deref().map(|vault| load_vault(vault, &mut errors)).unwrap_or_default();
+
+        let (template_root, template_layer) = pick(vault.template_root, global.template_root);
+        let (color_scheme, theme_layer) = pick(vault.color_scheme, global.color_scheme);
+        let (stages, stages_layer) = pick(vault.stages, global.stages);
+        let (ignore, ignore_layer) = pick(vault.ignore, global.ignore);
+        let base = self.default_stage_base();
+        self.template_root = template_root;
+        self.color_scheme = color_scheme.unwrap_or(ColorScheme::Green);
+        self.stages = stages.unwrap_or_else(|| default_stages(&base));
+        self.ignore = IgnoreList::new(ignore.unwrap_or_default());
+        self.setting_layers = [template_layer, theme_layer, stages_layer, ignore_layer];
+        errors
+    }
+
+    /// Where the built-in pipeline keeps its folders; a named vault without a vault config still keeps them at its top level
+    fn default_stage_base(&self) -> PathBuf {
+        self.vault
+            .clone()
+            .or_else(|| self.current_vault().map(|index| self.vaults[index].path.clone()))
+            .unwrap_or_else(|| self.root.clone())
+    }
+
+    /// Re-resolve settings after the root moves into or out of a vault
+    pub fn refresh_vault(&mut self) {
+        if find_vault(&self.root) != self.vault {
+            self.reload_layers();
+        }
+    }
+
+    /// Re-read the global config and resolve the layered settings for the current root
+    fn reload_layers(&mut self) {
+        let config = config::load_config();
+        let errors = self.apply_layers(config.settings);
+        let show = !errors.is_empty();
+        self.config_errors = config.errors;
+        self.config_errors.extend(errors);
+        if show {
+            self.mode = Mode::ConfigErrors;
+        }
+    }
+
+    fn write_setting(&self, setting: Setting, config: &mut Table, base: &Path) {
+        match setting {
+            Setting::TemplateRoot => set_or_remove(
+                config,
+                "template_root",
+                self.template_root.as_ref().map(|path| relative_to(path, base).to_string_lossy().to_string()),
+            ),
+            Setting::Theme => set(config, "theme", self.color_scheme.to_string()),
+            Setting::Stages => write_stages(&self.stages, config, base),
+            // Only ever written by hand
+            Setting::Ignore => {}
+        }
+    }
+
+    /// Write each overridable setting back to the layer it came from; global ones stay global from then on
+    pub fn write_layered_settings(&mut self, global: &mut Table) {
+        let mut to_vault = Vec::new();
+        for setting in [Setting::TemplateRoot, Setting::Theme, Setting::Stages] {
+            if self.layer_of(setting) == Layer::Vault {
+                to_vault.push(setting);
+                continue;
+            }
+            // The built-in pipeline follows the root around, so it's only written out once it's been changed
+            if setting == Setting::Stages && self.layer_of(setting) == Layer::Default && self.stages == default_stages(&self.default_stage_base()) {
+                continue;
+            }
+            self.write_setting(setting, global, Path::new(""));
+            let written = setting != Setting::TemplateRoot || self.template_root.is_some();
+            self.setting_layers[setting as usize] = if written { Layer::Global } else { Layer::Default };
+        }
+
+        let Some(vault) = self.vault.as_deref().filter(|_| !to_vault.is_empty()) else {
+            return;
+        };
+        let path = vault.join(VAULT_CONFIG);
+        // A vault config that doesn't parse is left for the user to fix rather than overwritten
+        let Some(mut doc) = fs::read_to_string(&path).ok().and_then(|content| content.parse::<DocumentMut>().ok()) else {
+            return;
+        };
+        for setting in to_vault {
+            self.write_setting(setting, doc.as_table_mut(), vault);
+        }
+        fs::write(path, doc.to_string()).ok();
+    }
+
+    /// Whether the pipeline's hooks come from a vault config the user hasn't trusted, or has changed since; they don't run until it is
+    pub fn hooks_need_trust(&self) -> bool {
+        self.layer_of(Setting::Stages) == Layer::Vault
+            && self.vault.as_ref().is_some_and(|vault| self.trusted_vaults.get(vault) != Some(&hooks_hash(&self.stages)))
+            && self.stages.iter().any(|stage| stage.pre_hook.is_some() || stage.post_hook.is_some())
+    }
+
+    /// Whether the current vault was trusted before, with hooks that have changed since
+    pub fn vault_hooks_changed(&self) -> bool {
+        self.hooks_need_trust() && self.vault.as_ref().is_some_and(|vault| self.trusted_vaults.contains_key(vault))
+    }
+
+    /// Let the current vault's hooks run from now on, remembering that in the global config
+    pub fn trust_vault_hooks(&mut self) {
+        if self.record_hook_trust() {
+            self.save_config();
+        }
+    }
+
+    /// Trust the current vault's hooks as they are now, without saving; false if there was nothing to trust
+    pub fn record_hook_trust(&mut self) -> bool {
+        let Some(vault) = self.vault.clone().filter(|_| self.hooks_need_trust()) else {
+            return false;
+        };
+        self.trusted_vaults.insert(vault, hooks_hash(&self.stages));
+        true
+    }
+
+    /// The folder vault-wide files like the history log live in: the vault being worked in, else the chosen root
+    pub fn vault_root(&self) -> PathBuf {
+        self.vault
+            .clone()
+            .or_else(|| self.current_vault().map(|index| self.vaults[index].path.clone()))
+            .unwrap_or_else(|| self.notes_root.clone())
+    }
+
+    /// The named vault holding the root; the innermost one if they nest
+    pub fn current_vault(&self) -> Option<usize> {
+        let root = absolute_path(&self.root);
+        (0..self.vaults.len())
+            .filter(|&index| root.starts_with(&self.vaults[index].path))
+            .max_by_key(|&index| self.vaults[index].path.components().count())
+    }
+
+    /// Note the selected file and open buffers against the current named vault
+    pub fn remember_vault_session(&mut self) {
+        let Some(index) = self.current_vault() else {
+            return;
+        };
+        let last_file = self.selected_file().map(|path| absolute_path(path));
+        let open_buffers = self.buffers.iter().map(|buffer| absolute_path(&buffer.path)).collect();
+        let vault = &mut self.vaults[index];
+        vault.last_file = last_file;
+        vault.open_buffers = open_buffers;
+    }
+
+    pub fn open_vault_switcher(&mut self) {
+        let mut list_state = ListState::default();
+        list_state.select(if self.vaults.is_empty() { None } else { Some(self.current_vault().unwrap_or(0)) });
+        self.vault_switcher = Some(VaultSwitcherState { list_state, error: None });
+        self.mode = Mode::VaultSwitcher;
+    }
+
+    pub fn close_vault_switcher(&mut self) {
+        self.vault_switcher = None;
+        self.mode = Mode::Normal;
+    }
+
+    pub fn vault_switcher_select(&mut self, forward: bool) {
+        let count = self.vaults.len();
+        let Some(switcher) = self.vault_switcher.as_mut().filter(|_| count > 0) else {
+            return;
+        };
+        let current = switcher.list_state.selected().unwrap_or(0);
+        let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
+        switcher.list_state.select(Some(next));
+        switcher.error = None;
+    }
+
+    pub fn vault_switcher_open(&mut self) {
+        let Some(index) = self.vault_switcher.as_ref().and_then(|switcher| switcher.list_state.selected()) else {
+            return;
+        };
+        if !self.has_unsaved_buffers() {
+            self.remember_vault_session();
+        }
+        match self.switch_vault(index) {
+            Ok(()) => self.vault_switcher = None,
+            Err(error) => {
+                if let Some(switcher) = self.vault_switcher.as_mut() {
+                    switcher.error = Some(error);
+                }
+            }
+        }
+    }
+
+    /// Make a named vault the root, resolving its settings and restoring the file and buffers it was left with; the session being left should be remembered first
+    pub fn switch_vault(&mut self, index: usize) -> Result<(), String> {
+        if self.has_unsaved_buffers() {
+            return Err("Save or close unsaved notes before switching vaults".to_string());
+        }
+        let Some(vault) = self.vaults.get(index) else {
+            return Ok(());
+        };
+        let last_file = vault.last_file.clone().filter(|path| path.exists());
+        let open_buffers: Vec<PathBuf> = vault.open_buffers.iter().filter(|path| path.is_file()).cloned().collect();
+        // Land in the folder the last file was selected in, as long as it's still inside the vault
+        self.root = last_file
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|folder| folder.starts_with(&vault.path) && folder.is_dir())
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| vault.path.clone());
+
+        // Before the layers are resolved, which may open the error popup
+        self.mode = Mode::Normal;
+        self.reload_layers();
+        // Opening a buffer saves the config, so it waits until the new vault's settings are in place
+        let mode = self.mode;
+        self.split = None;
+        self.buffers.clear();
+        self.active_buffer = 0;
+        for path in &open_buffers {
+            self.open_buffer(path);
+        }
+        self.mode = mode;
+        self.load_files();
+        let selected = last_file.and_then(|last_file| self.files.iter().position(|file| absolute_path(file) == last_file));
+        self.file_list_state.select(selected.or(if self.files.is_empty() { None } else { Some(0) }));
+        self.save_config();
+        Ok(())
+    }
+}
@@ -0,0 +1,361 @@
+use ratatui::style::Color;
+use std::{fs, path::{Path, PathBuf}, str::FromStr};
+use toml_edit::{ArrayOfTables, Item, Table};
+
+use crate::app::App;
+use crate::config::{set, set_or_remove, string_array, Section};
+use crate::vault::relative_to;
+use crate::constants::DEFAULT_MOVE_DESTINATIONS;
+use crate::modes::Mode;
+
+/// Colors the pipeline editor cycles through
+const STAGE_COLORS: &[Color] = &[
+    Color::Yellow,
+    Color::Cyan,
+    Color::Green,
+    Color::Magenta,
+    Color::Blue,
+    Color::Red,
+    Color::White,
+];
+
+/// Name of the notes root as a place in the pipeline, usable in a stage's `next`
+pub const INBOX: &str = "Inbox";
+
+/// One step of the workflow pipeline, backed by a folder
+#[derive(PartialEq, Clone)]
+pub struct WorkflowStage {
+    pub name: String,
+    pub folder: PathBuf,
+    pub color: Color,
+    pub icon: String,
+    /// Stages a note here may move to; `None` allows any, an empty list none
+    pub transitions: Option<Vec<String>>,
+    pub wip_limit: Option<usize>,
+    pub pre_hook: Option<String>,
+    pub post_hook: Option<String>,
+}
+
+impl WorkflowStage {
+    pub fn new(name: &str, folder: PathBuf, color: Color) -> Self {
+        Self {
+            name: name.to_string(),
+            folder,
+            color,
+            icon: "■".to_string(),
+            transitions: None,
+            wip_limit: None,
+            pre_hook: None,
+            post_hook: None,
+        }
+    }
+
+    pub fn allows(&self, target: &str) -> bool {
+        self.transitions.as_ref().is_none_or(|names| names.iter().any(|name| name.eq_ignore_ascii_case(target)))
+    }
+
+    /// The allowed next stages as shown to the user
+    pub fn transitions_label(&self) -> String {
+        match &self.transitions {
+            None => "any".to_string(),
+            Some(names) if names.is_empty() => "none".to_string(),
+            Some(names) => names.join(", "),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum SettingsSection {
+    Themes,
+    Pipeline,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum StageField {
+    Name,
+    Folder,
+    Icon,
+    Transitions,
+    WipLimit,
+    PreHook,
+    PostHook,
+}
+
+impl StageField {
+    pub fn label(self) -> &'static str {
+        match self {
+            StageField::Name => "STAGE NAME",
+            StageField::Folder => "STAGE FOLDER",
+            StageField::Icon => "STAGE ICON",
+            StageField::Transitions => "NEXT STAGES (COMMA SEPARATED, EMPTY = ANY, NONE = FINAL)",
+            StageField::WipLimit => "WIP LIMIT (EMPTY = NONE)",
+            StageField::PreHook => "PRE-MOVE HOOK COMMAND (NON-ZERO EXIT CANCELS)",
+            StageField::PostHook => "POST-MOVE HOOK COMMAND",
+        }
+    }
+}
+
+const STAGE_KEYS: &[&str] = &["name", "folder", "color", "icon", "next", "wip", "pre_hook", "post_hook"];
+
+/// Read the `[[stages]]` tables, resolving relative folders against `base`; `None` when there are none
+pub fn read_stages(config: &Section, base: &Path, errors: &mut Vec<String>) -> Option<Vec<WorkflowStage>> {
+    let mut stages = Vec::new();
+    for stage in config.sections("stages", errors) {
+        stage.check_keys(STAGE_KEYS, errors);
+        let Some(name) = stage.str("name", errors).map(str::trim).filter(|name| !name.is_empty()) else {
+            errors.push(format!("{}: a stage needs a name", stage.name));
+            continue;
+        };
+        let color = stage.str("color", errors).and_then(|color| {
+            let parsed = Color::from_str(color).ok();
+            if parsed.is_none() {
+                errors.push(format!("{}: unknown color '{}'", stage.key_path("color"), color));
+            }
+            parsed
+        });
+        stages.push(WorkflowStage {
+            name: name.to_string(),
+            folder: base.join(stage.path("folder", errors).unwrap_or_else(|| PathBuf::from(name))),
+            color: color.unwrap_or(Color::Reset),
+            icon: stage.str("icon", errors).filter(|icon| !icon.is_empty()).unwrap_or("■").to_string(),
+            transitions: stage.strings("next", errors).map(|names| {
+                names.into_iter().map(str::trim).filter(|name| !name.is_empty()).map(String::from).collect()
+            }),
+            wip_limit: stage.positive("wip", errors),
+            pre_hook: stage.str("pre_hook", errors).filter(|hook| !hook.is_empty()).map(String::from),
+            post_hook: stage.str("post_hook", errors).filter(|hook| !hook.is_empty()).map(String::from),
+        });
+    }
+    (!stages.is_empty()).then_some(stages)
+}
+
+/// The built-in pipeline, with a folder per stage under `root`
+pub fn default_stages(root: &Path) -> Vec<WorkflowStage> {
+    DEFAULT_MOVE_DESTINATIONS
+        .iter()
+        .map(|destination| WorkflowStage::new(destination, root.join(destination), Color::Reset))
+        .collect()
+}
+
+/// Update the `[[stages]]` tables in place so comments on unchanged stages survive; folders under `base` are written relative to it
+pub fn write_stages(stages: &[WorkflowStage], config: &mut Table, base: &Path) {
+    if !config.get("stages").is_some_and(Item::is_array_of_tables) {
+        config.insert("stages", Item::ArrayOfTables(ArrayOfTables::new()));
+    }
+    let Some(tables) = config.get_mut("stages").and_then(Item::as_array_of_tables_mut) else {
+        return;
+    };
+    while tables.len() > stages.len() {
+        tables.remove(tables.len() - 1);
+    }
+    for (index, stage) in stages.iter().enumerate() {
+        if index == tables.len() {
+            tables.push(Table::new());
+        }
+        let Some(table) = tables.get_mut(index) else {
+            continue;
+        };
+        set(table, "name", stage.name.as_str());
+        set(table, "folder", relative_to(&stage.folder, base).to_string_lossy().as_ref());
+        set_or_remove(table, "color", (stage.color != Color::Reset).then(|| stage.color.to_string()));
+        set(table, "icon", stage.icon.as_str());
+        set_or_remove(table, "next", stage.transitions.as_ref().map(|names| string_array(names.iter().map(String::as_str))));
+        set_or_remove(table, "wip", stage.wip_limit.map(|limit| limit as i64));
+        set_or_remove(table, "pre_hook", stage.pre_hook.as_deref());
+        set_or_remove(table, "post_hook", stage.post_hook.as_deref());
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn stage_color(&self, stage: &WorkflowStage) -> Color {
+        if stage.color == Color::Reset {
+            self.color_scheme.primary_color()
+        } else {
+            stage.color
+        }
+    }
+
+    pub fn stage_by_name(&self, name: &str) -> Option<&WorkflowStage> {
+        self.stages.iter().find(|stage| stage.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The stage whose folder holds `path`
+    pub fn stage_of(&self, path: &Path) -> Option<usize> {
+        let parent = path.parent()?;
+        let parent = fs::canonicalize(parent).unwrap_or_else(|_| parent.to_path_buf());
+        self.stages.iter().position(|stage| {
+            fs::canonicalize(&stage.folder).unwrap_or_else(|_| stage.folder.clone()) == parent
+        })
+    }
+
+    /// Stages a note at `path` may legally move to; notes outside the pipeline may enter anywhere
+    pub fn legal_moves(&self, path: &Path) -> Vec<usize> {
+        let current = self.stage_of(path);
+        (0..self.stages.len())
+            .filter(|&index| Some(index) != current)
+            .filter(|&index| current.is_none_or(|current| self.stages[current].allows(&self.stages[index].name)))
+            .collect()
+    }
+
+    /// Whether a note may leave its stage for the Inbox; like any other move it must be listed in the stage's `next`
+    pub fn may_move_to_inbox(&self, path: &Path) -> bool {
+        self.stage_of(path).is_some_and(|current| self.stages[current].allows(INBOX))
+    }
+
+    /// Move a note into `folder`, keeping open buffers and the file list in step; a note already there is never replaced
+    pub fn move_note(&mut self, path: &Path, folder: &Path) -> Option<PathBuf> {
+        let new_path = folder.join(path.file_name()?);
+        let from = self.stage_name_of(path);
+        fs::create_dir_all(folder).ok();
+        let moved = !new_path.exists() && fs::rename(path, &new_path).is_ok();
+        if moved {
+            self.retarget_buffer(path, &new_path);
+            self.retarget_bookmarks(path, &new_path);
+            let to = self.stage_name_of(&new_path);
+            self.record_transition(&new_path, from, to);
+        }
+
+        self.load_files();
+        if !self.files.is_empty() {
+            let new_selection = self.file_list_state.selected().unwrap_or(0).min(self.files.len() - 1);
+            self.file_list_state.select(Some(new_selection));
+        } else {
+            self.file_list_state.select(None);
+        }
+        if !self.search_input.is_empty() {
+            self.update_filtered_files();
+        }
+        moved.then_some(new_path)
+    }
+
+    pub fn selected_stage_index(&self) -> Option<usize> {
+        self.settings_selection_state.selected().filter(|&index| index < self.stages.len())
+    }
+
+    pub fn toggle_settings_section(&mut self) {
+        self.settings_section = match self.settings_section {
+            SettingsSection::Themes => SettingsSection::Pipeline,
+            SettingsSection::Pipeline => SettingsSection::Themes,
+        };
+        self.settings_selection_state.select(Some(0));
+    }
+
+    pub fn add_stage(&mut self) {
+        let name = format!("Stage {}", self.stages.len() + 1);
+        let folder = self.root.join(&name);
+        self.stages.push(WorkflowStage::new(&name, folder, Color::Reset));
+        self.settings_selection_state.select(Some(self.stages.len() - 1));
+        self.save_config();
+    }
+
+    pub fn delete_stage(&mut self) {
+        let Some(index) = self.selected_stage_index() else {
+            return;
+        };
+        let removed = self.stages.remove(index);
+        // A stage that could only move to the removed one is left with no next stage, not opened up to all of them
+        for names in self.stages.iter_mut().filter_map(|stage| stage.transitions.as_mut()) {
+            names.retain(|name| !name.eq_ignore_ascii_case(&removed.name));
+        }
+        let selection = index.min(self.stages.len().saturating_sub(1));
+        self.settings_selection_state.select(Some(selection));
+        self.save_config();
+    }
+
+    /// Shift the selected stage one place earlier or later in the pipeline
+    pub fn reorder_stage(&mut self, later: bool) {
+        let Some(index) = self.selected_stage_index() else {
+            return;
+        };
+        let target = if later { index + 1 } else { index.wrapping_sub(1) };
+        if target < self.stages.len() {
+            self.stages.swap(index, target);
+            self.settings_selection_state.select(Some(target));
+            self.save_config();
+        }
+    }
+
+    pub fn cycle_stage_color(&mut self) {
+        let Some(index) = self.selected_stage_index() else {
+            return;
+        };
+        let stage = &mut self.stages[index];
+        stage.color = match STAGE_COLORS.iter().position(|color| *color == stage.color) {
+            Some(i) if i + 1 < STAGE_COLORS.len() => STAGE_COLORS[i + 1],
+            Some(_) => Color::Reset,
+            None => STAGE_COLORS[0],
+        };
+        self.save_config();
+    }
+
+    pub fn start_stage_edit(&mut self, field: StageField) {
+        let Some(index) = self.selected_stage_index() else {
+            return;
+        };
+        let stage = &self.stages[index];
+        self.filename_input = match field {
+            StageField::Name => stage.name.clone(),
+            StageField::Folder => stage.folder.to_string_lossy().into_owned(),
+            StageField::Icon => stage.icon.clone(),
+            StageField::Transitions => match &stage.transitions {
+                Some(names) if names.is_empty() => "none".to_string(),
+                names => names.as_deref().unwrap_or_default().join(", "),
+            },
+            StageField::WipLimit => stage.wip_limit.map(|limit| limit.to_string()).unwrap_or_default(),
+            StageField::PreHook => stage.pre_hook.clone().unwrap_or_default(),
+            StageField::PostHook => stage.post_hook.clone().unwrap_or_default(),
+        };
+        self.stage_field = field;
+        self.mode = Mode::EditingStage;
+    }
+
+    pub fn apply_stage_edit(&mut self) {
+        let value = std::mem::take(&mut self.filename_input).trim().to_string();
+        self.mode = Mode::Settings;
+        let Some(index) = self.selected_stage_index() else {
+            return;
+        };
+        let was_trusted = !self.hooks_need_trust();
+
+        match self.stage_field {
+            StageField::Name if !value.is_empty() => {
+                let old_name = std::mem::replace(&mut self.stages[index].name, value.clone());
+                for stage in &mut self.stages {
+                    for name in stage.transitions.iter_mut().flatten() {
+                        if name.eq_ignore_ascii_case(&old_name) {
+                            *name = value.clone();
+                        }
+                    }
+                }
+            }
+            StageField::Folder if !value.is_empty() => {
+                let folder = PathBuf::from(&value);
+                self.stages[index].folder = if folder.is_absolute() { folder } else { self.root.join(folder) };
+            }
+            StageField::Icon if !value.is_empty() => self.stages[index].icon = value,
+            StageField::Transitions => {
+                self.stages[index].transitions = if value.is_empty() {
+                    None
+                } else if value.eq_ignore_ascii_case("none") {
+                    Some(Vec::new())
+                } else {
+                    Some(value.split(',').map(str::trim).filter(|name| !name.is_empty()).map(String::from).collect())
+                };
+            }
+            StageField::WipLimit => self.stages[index].wip_limit = value.parse().ok().filter(|limit| *limit > 0),
+            StageField::PreHook => self.stages[index].pre_hook = Some(value).filter(|hook| !hook.is_empty()),
+            StageField::PostHook => self.stages[index].post_hook = Some(value).filter(|hook| !hook.is_empty()),
+            _ => return,
+        }
+        // Hooks typed in here have been seen, so they don't need trusting again
+        if was_trusted {
+            self.record_hook_trust();
+        }
+        self.save_config();
+    }
+
+    pub fn cancel_stage_edit(&mut self) {
+        self.filename_input.clear();
+        self.mode = Mode::Settings;
+    }
+}